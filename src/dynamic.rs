@@ -0,0 +1,170 @@
+use std::{cell::Cell, time::Duration};
+
+/// A deterministic clock with its own state and its own configuration, created by a [`DynamicClockBuilder`]
+///
+/// Like [`IsolatedClock`](crate::isolated::IsolatedClock) this doesn't use any global or thread-local state, so a
+/// test can hold several of these, each configured differently.
+#[derive(Debug)]
+pub struct DynamicClock {
+    time: Cell<Duration>,
+    speed: f64,
+    auto_advance: Duration,
+    resolution: Duration,
+}
+
+impl DynamicClock {
+    /// Start configuring a new clock
+    pub fn builder() -> DynamicClockBuilder {
+        DynamicClockBuilder::new()
+    }
+
+    /// Set this clock to this [`Duration`]
+    pub fn set_time(&self, time: Duration) {
+        self.time.set(time);
+    }
+
+    /// Advance this clock by this [`Duration`], multiplied by the clock's speed
+    pub fn advance(&self, time: Duration) {
+        self.time.set(self.time.get() + time.mul_f64(self.speed));
+    }
+
+    /// Get the current time of this clock, floored to its resolution
+    ///
+    /// If the clock auto-advances, it is then advanced by that step, so the next read is later.
+    pub fn time(&self) -> Duration {
+        let time = self.time.get();
+        self.time.set(time + self.auto_advance);
+        crate::quantize(time, self.resolution)
+    }
+
+    /// The factor advances are multiplied by
+    pub const fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// How far the clock moves after each read of [`DynamicClock::time`]
+    pub const fn auto_advance(&self) -> Duration {
+        self.auto_advance
+    }
+
+    /// The resolution reads of the clock are floored to, `Duration::ZERO` if they aren't
+    pub const fn resolution(&self) -> Duration {
+        self.resolution
+    }
+}
+
+/// Configures a [`DynamicClock`]
+///
+/// By default the clock starts at `Duration::ZERO`, at a speed of `1.0`, without auto-advancing or a resolution.
+#[derive(Debug, Clone)]
+pub struct DynamicClockBuilder {
+    start: Duration,
+    speed: f64,
+    auto_advance: Duration,
+    resolution: Duration,
+}
+
+impl Default for DynamicClockBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DynamicClockBuilder {
+    /// Create a builder with the default configuration
+    pub const fn new() -> Self {
+        Self {
+            start: Duration::ZERO,
+            speed: 1.0,
+            auto_advance: Duration::ZERO,
+            resolution: Duration::ZERO,
+        }
+    }
+
+    /// Start the clock at this [`Duration`]
+    pub const fn start(mut self, start: Duration) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Multiply every advance of the clock by `speed`
+    ///
+    /// # Panics
+    /// If `speed` is negative or not finite
+    #[track_caller]
+    pub fn speed(mut self, speed: f64) -> Self {
+        assert!(
+            speed.is_finite() && speed >= 0.0,
+            "speed must be finite and not negative, got {speed}"
+        );
+        self.speed = speed;
+        self
+    }
+
+    /// Advance the clock by `step` after every read
+    pub const fn auto_advance(mut self, step: Duration) -> Self {
+        self.auto_advance = step;
+        self
+    }
+
+    /// Floor every read of the clock to a multiple of `resolution`
+    pub const fn resolution(mut self, resolution: Duration) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Create the configured clock
+    pub fn build(&self) -> DynamicClock {
+        DynamicClock {
+            time: Cell::new(self.start),
+            speed: self.speed,
+            auto_advance: self.auto_advance,
+            resolution: self.resolution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn independent_speeds() {
+        let normal = DynamicClock::builder().build();
+        let fast = DynamicClock::builder().speed(2.0).build();
+        let slow = DynamicClock::builder()
+            .start(Duration::from_secs(10))
+            .speed(0.5)
+            .build();
+
+        for clock in [&normal, &fast, &slow] {
+            clock.advance(Duration::from_secs(3));
+        }
+
+        assert_eq!(normal.time(), Duration::from_secs(3));
+        assert_eq!(fast.time(), Duration::from_secs(6));
+        assert_eq!(slow.time(), Duration::from_millis(11_500));
+        assert_eq!(fast.speed(), 2.0);
+
+        fast.set_time(Duration::ZERO);
+        assert_eq!(fast.time(), Duration::ZERO);
+        assert_eq!(normal.time(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn auto_advance_and_resolution() {
+        let clock = DynamicClock::builder()
+            .auto_advance(Duration::from_millis(400))
+            .resolution(Duration::from_secs(1))
+            .build();
+
+        let reads: Vec<_> = (0..4).map(|_| clock.time()).collect();
+        assert_eq!(reads, [0, 0, 0, 1].map(Duration::from_secs));
+    }
+
+    #[test]
+    #[should_panic(expected = "speed must be finite and not negative")]
+    fn negative_speed() {
+        let _ = DynamicClock::builder().speed(-1.0);
+    }
+}
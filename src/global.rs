@@ -1,51 +1,324 @@
-use std::{sync::Mutex, time::Duration};
+use std::{
+    cell::Cell,
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, TryLockError,
+    },
+    time::Duration,
+};
 
 static TIME: Mutex<Duration> = Mutex::new(Duration::ZERO);
+// the last time seen through `TIME`, in nanoseconds
+static CACHED_TIME: AtomicU64 = AtomicU64::new(0);
 static SYSTEM_TIME: Mutex<Duration> = Mutex::new(Duration::ZERO);
+static BOOT_TIME: Mutex<Duration> = Mutex::new(Duration::ZERO);
+static LAST_OP: Mutex<Option<crate::ClockOp>> = Mutex::new(None);
+static ADVANCE_CALLBACKS: Mutex<Vec<AdvanceCallback>> = Mutex::new(Vec::new());
+// held by the thread running the callbacks, so other threads wait for them instead of finding the list empty
+static RUNNING_CALLBACKS: Mutex<()> = Mutex::new(());
+// the (instant, system_time) resolutions
+static RESOLUTION: Mutex<(Duration, Duration)> = Mutex::new((Duration::ZERO, Duration::ZERO));
+static FRAME_RATE: Mutex<f64> = Mutex::new(60.0);
+static PROCESS_START: Mutex<Duration> = Mutex::new(Duration::ZERO);
+static REALTIME: Mutex<Option<crate::Realtime>> = Mutex::new(None);
+static NOW_CALLS: AtomicU64 = AtomicU64::new(0);
+static SYSTEM_PAUSED: AtomicBool = AtomicBool::new(false);
+static STRICTLY_MONOTONIC: AtomicBool = AtomicBool::new(false);
+static LAST_TOKEN: Mutex<Option<(u128, u64)>> = Mutex::new(None);
+static FLOOR: Mutex<(Duration, crate::FloorMode)> =
+    Mutex::new((Duration::ZERO, crate::FloorMode::Error));
+// the system time that reads are stalled at, and how many reads are left
+static SYSTEM_STALL: Mutex<Option<(Duration, usize)>> = Mutex::new(None);
 
-fn with_time(d: impl Fn(&mut Duration)) {
+type AdvanceCallback = Box<dyn FnMut(Duration) -> ControlFlow<()> + Send>;
+
+std::thread_local! {
+    // the (time, system_time) that `now()` returns on this thread, inside of `MockClock::with_frozen_now`
+    static FROZEN_NOW: Cell<Option<(Duration, Duration)>> = const { Cell::new(None) };
+    // is this thread running the advance callbacks?
+    static IN_CALLBACKS: Cell<bool> = const { Cell::new(false) };
+}
+
+fn with_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
+    // captures the process uptime baseline on the first use of the clock
+    crate::process_start();
     let mut t = TIME.lock().unwrap();
-    d(&mut t);
+    // moves any realtime progress into the stored time
+    if let Some(realtime) = &mut *REALTIME.lock().unwrap() {
+        *t += realtime.restart();
+    }
+    let out = d(&mut t);
+    cache_time(*t);
+    out
 }
 
 fn get_time() -> Duration {
-    *TIME.lock().unwrap()
+    // captures the process uptime baseline on the first use of the clock
+    crate::process_start();
+    let t = *TIME.lock().unwrap() + realtime_elapsed();
+    cache_time(t);
+    t
+}
+
+fn realtime_elapsed() -> Duration {
+    REALTIME
+        .lock()
+        .unwrap()
+        .map_or(Duration::ZERO, |rt| rt.elapsed())
+}
+
+fn get_realtime() -> Option<crate::Realtime> {
+    *REALTIME.lock().unwrap()
 }
 
-fn with_system_time(d: impl Fn(&mut Duration)) {
+fn set_realtime(realtime: Option<crate::Realtime>) {
+    with_time(|_| *REALTIME.lock().unwrap() = realtime);
+}
+
+fn cache_time(time: Duration) {
+    let nanos = u64::try_from(time.as_nanos()).unwrap_or(u64::MAX);
+    CACHED_TIME.store(nanos, Ordering::Relaxed);
+}
+
+fn with_system_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
     let mut t = SYSTEM_TIME.lock().unwrap();
-    d(&mut t);
+    d(&mut t)
+}
+
+// updates (time, system_time) together, so they don't have to be nested
+fn with_clocks<R>(d: impl FnOnce(&mut Duration, &mut Duration) -> R) -> R {
+    with_time(|t| d(t, &mut SYSTEM_TIME.lock().unwrap()))
 }
 
 fn get_system_time() -> Duration {
     *SYSTEM_TIME.lock().unwrap()
 }
 
+fn with_boot_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
+    let mut t = BOOT_TIME.lock().unwrap();
+    d(&mut t)
+}
+
+fn get_boot_time() -> Duration {
+    *BOOT_TIME.lock().unwrap()
+}
+
+fn with_resolution<R>(d: impl FnOnce(&mut (Duration, Duration)) -> R) -> R {
+    d(&mut RESOLUTION.lock().unwrap())
+}
+
+fn get_resolution() -> (Duration, Duration) {
+    *RESOLUTION.lock().unwrap()
+}
+
+fn set_frame_rate(fps: f64) {
+    *FRAME_RATE.lock().unwrap() = fps;
+}
+
+fn get_frame_rate() -> f64 {
+    *FRAME_RATE.lock().unwrap()
+}
+
+fn set_process_start(start: Duration) {
+    *PROCESS_START.lock().unwrap() = start;
+}
+
+fn get_process_start() -> Duration {
+    *PROCESS_START.lock().unwrap()
+}
+
+fn count_now_call() {
+    NOW_CALLS.fetch_add(1, Ordering::Relaxed);
+}
+
+fn get_now_calls() -> u64 {
+    NOW_CALLS.load(Ordering::Relaxed)
+}
+
+fn reset_now_calls() {
+    NOW_CALLS.store(0, Ordering::Relaxed);
+}
+
+fn set_system_paused(paused: bool) {
+    SYSTEM_PAUSED.store(paused, Ordering::SeqCst);
+}
+
+fn is_system_paused() -> bool {
+    SYSTEM_PAUSED.load(Ordering::SeqCst)
+}
+
+fn set_strictly_monotonic(enabled: bool) {
+    STRICTLY_MONOTONIC.store(enabled, Ordering::SeqCst);
+}
+
+fn is_strictly_monotonic() -> bool {
+    STRICTLY_MONOTONIC.load(Ordering::SeqCst)
+}
+
+fn get_frozen_now() -> Option<(Duration, Duration)> {
+    FROZEN_NOW.with(|t| t.get())
+}
+
+fn set_frozen_now(frozen: Option<(Duration, Duration)>) {
+    FROZEN_NOW.with(|t| t.set(frozen));
+}
+
+fn with_last_token<R>(d: impl FnOnce(&mut Option<(u128, u64)>) -> R) -> R {
+    d(&mut LAST_TOKEN.lock().unwrap())
+}
+
+fn with_floor<R>(d: impl FnOnce(&mut (Duration, crate::FloorMode)) -> R) -> R {
+    d(&mut FLOOR.lock().unwrap())
+}
+
+fn get_floor() -> (Duration, crate::FloorMode) {
+    *FLOOR.lock().unwrap()
+}
+
+fn with_system_stall<R>(d: impl FnOnce(&mut Option<(Duration, usize)>) -> R) -> R {
+    d(&mut SYSTEM_STALL.lock().unwrap())
+}
+
+fn set_last_op(op: crate::ClockOp) {
+    *LAST_OP.lock().unwrap() = Some(op);
+}
+
+fn get_last_op() -> Option<crate::ClockOp> {
+    *LAST_OP.lock().unwrap()
+}
+
+fn add_advance_callback(f: AdvanceCallback) {
+    ADVANCE_CALLBACKS.lock().unwrap().push(f);
+}
+
+fn run_advance_callbacks(d: Duration) {
+    struct Running;
+    impl Drop for Running {
+        fn drop(&mut self) {
+            IN_CALLBACKS.with(|t| t.set(false));
+        }
+    }
+
+    // a callback advancing the clock doesn't run the callbacks again
+    if IN_CALLBACKS.with(|t| t.get()) {
+        return;
+    }
+    let _running = RUNNING_CALLBACKS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    IN_CALLBACKS.with(|t| t.set(true));
+    let _reset = Running;
+
+    // the callbacks are taken out while they run, so they can use the clock themselves
+    let mut callbacks = std::mem::take(&mut *ADVANCE_CALLBACKS.lock().unwrap());
+    callbacks.retain_mut(|f| f(d).is_continue());
+
+    let mut current = ADVANCE_CALLBACKS.lock().unwrap();
+    callbacks.append(&mut current);
+    *current = callbacks;
+}
+
 crate::macros::define_mock_clock! {
     false;
     /// This uses a global mutex state for the deterministic clock
 }
 
+impl MockClock {
+    /// Get the current [`Instant`] duration, without waiting on the clock's lock
+    ///
+    /// If the lock is held elsewhere, this returns the last time that was read or written through the clock,
+    /// which may be stale. The cached value saturates at `u64::MAX` nanoseconds.
+    pub fn time_relaxed() -> Duration {
+        let stored = match TIME.try_lock() {
+            Ok(t) => Some(*t),
+            Err(TryLockError::Poisoned(t)) => Some(*t.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        let time = match stored {
+            Some(t) => {
+                let t = t + realtime_elapsed();
+                cache_time(t);
+                t
+            }
+            None => Duration::from_nanos(CACHED_TIME.load(Ordering::Relaxed)),
+        };
+        crate::quantize(time, get_resolution().0)
+    }
+}
+
+/// The [`ClockMode`](crate::ClockMode) of this module's [`MockClock`], which shares its state across threads
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GlobalMode;
+
+impl crate::ClockMode for GlobalMode {
+    const IS_THREAD_LOCAL: bool = false;
+}
+
+impl crate::HasClockMode for MockClock {
+    type Mode = GlobalMode;
+}
+
+impl MockClock {
+    /// The mode of this [`MockClock`], as a marker type
+    pub const MODE: GlobalMode = GlobalMode;
+}
+
 crate::macros::define_instant! {
     MockClock::time;
     false;
     /// This uses a global mutex for its time source
 }
 
+/// Reinterpret a [`thread_local::Instant`](crate::thread_local::Instant) as an [`Instant`] on this module's clock
+///
+/// This copies the instant's offset from its clock's start. The two clocks aren't connected, so the converted
+/// [`Instant`] is only meaningful if this clock was kept in step with the calling thread's thread-local clock.
+impl From<crate::thread_local::Instant> for Instant {
+    fn from(value: crate::thread_local::Instant) -> Self {
+        Self::from(value.into_inner())
+    }
+}
+
 crate::macros::define_system_time! {
     MockClock::system_time;
     false;
     /// This uses a global mutex for its time source
 }
 
+crate::macros::define_boot_instant! {
+    MockClock::boot_time;
+    false;
+    /// This uses a global mutex for its time source
+}
+
 crate::macros::define_instant_tests!();
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn mode_marker() {
+        use crate::{ClockMode, HasClockMode};
+
+        fn is_thread_local<C: HasClockMode>() -> bool {
+            C::Mode::IS_THREAD_LOCAL
+        }
+        fn requires_mode<C: HasClockMode<Mode = GlobalMode>>() {}
+
+        requires_mode::<MockClock>();
+        assert_eq!(is_thread_local::<MockClock>(), MockClock::is_thread_local());
+        assert_eq!(MockClock::MODE, GlobalMode);
+        assert_ne!(
+            <MockClock as HasClockMode>::Mode::IS_THREAD_LOCAL,
+            crate::thread_local::ThreadLocalMode::IS_THREAD_LOCAL
+        );
+    }
+
     #[test]
     fn is_thread_local() {
+        let _lock = crate::test_lock();
         assert!(!MockClock::is_thread_local());
         assert!(!Instant::now().is_thread_local());
         assert!(!SystemTime::now().is_thread_local());
@@ -53,6 +326,7 @@ mod tests {
 
     #[test]
     fn thread_sharing() {
+        let _lock = crate::test_lock();
         MockClock::set_time(Duration::ZERO);
 
         let start = Instant::now();
@@ -78,4 +352,113 @@ mod tests {
         // the creation/locking time may be in microseconds so we'll have something like 43.002 != 43
         assert_eq!(start.elapsed().as_secs(), 43);
     }
+
+    #[test]
+    fn time_relaxed() {
+        let _lock = crate::test_lock();
+
+        MockClock::set_time(Duration::from_secs(3));
+        assert_eq!(MockClock::time_relaxed(), Duration::from_secs(3));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let held = TIME.lock().unwrap();
+        let handle = std::thread::spawn(move || tx.send(MockClock::time_relaxed()).unwrap());
+
+        let time = rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("time_relaxed should not block on a held lock");
+        assert_eq!(time, Duration::from_secs(3));
+
+        drop(held);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn time_relaxed_poisoned() {
+        let _lock = crate::test_lock();
+        MockClock::set_time(Duration::from_secs(3));
+        MockClock::set_realtime_scaled(1000.0);
+
+        std::thread::spawn(|| {
+            let _held = TIME.lock().unwrap();
+            panic!("poisoning the clock");
+        })
+        .join()
+        .unwrap_err();
+
+        // the realtime progress is added, as it is by `time()`
+        std::thread::sleep(Duration::from_millis(5));
+        let time = MockClock::time_relaxed();
+
+        TIME.clear_poison();
+        MockClock::stop_realtime();
+        assert!(time >= Duration::from_secs(8), "{time:?}");
+        MockClock::reset_time();
+    }
+
+    #[test]
+    fn convert_between_modules() {
+        let instant = Instant::from(Duration::new(3, 5));
+
+        let thread_local = crate::thread_local::Instant::from(instant);
+        assert_eq!(thread_local.into_inner(), Duration::new(3, 5));
+        assert_eq!(Instant::from(thread_local), instant);
+    }
+
+    #[test]
+    fn advance_callbacks_concurrently() {
+        use std::sync::{atomic::AtomicUsize, Arc};
+
+        let _lock = crate::test_lock();
+        const THREADS: usize = 8;
+        const ADVANCES: usize = 2_000;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        MockClock::on_advance_cancellable(move |_| {
+            // removes itself after the last expected advance
+            match counter.fetch_add(1, Ordering::SeqCst) + 1 {
+                n if n == THREADS * ADVANCES => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        });
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    for _ in 0..ADVANCES {
+                        MockClock::advance(Duration::from_nanos(1));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), THREADS * ADVANCES);
+        MockClock::reset_time();
+    }
+
+    #[test]
+    fn strictly_monotonic_concurrently() {
+        let _lock = crate::test_lock();
+        MockClock::reset_time();
+        MockClock::set_strictly_monotonic(true);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| (0..1_000).map(|_| Instant::now()).collect::<Vec<_>>()))
+            .collect();
+        let mut reads: Vec<_> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        MockClock::set_strictly_monotonic(false);
+
+        let total = reads.len();
+        reads.sort();
+        reads.dedup();
+        assert_eq!(reads.len(), total);
+        MockClock::reset_time();
+    }
 }
@@ -0,0 +1,124 @@
+use std::{cell::Cell, time::Duration};
+
+/// A deterministic clock with its own state
+///
+/// Unlike the `MockClock`s, this doesn't use any global or thread-local state. It has to be passed around explicitly,
+/// and the [`IsolatedInstant`]s it creates borrow it.
+#[derive(Debug, Default)]
+pub struct IsolatedClock {
+    time: Cell<Duration>,
+}
+
+impl IsolatedClock {
+    /// Create a new clock, starting at `Duration::ZERO`
+    pub const fn new() -> Self {
+        Self {
+            time: Cell::new(Duration::ZERO),
+        }
+    }
+
+    /// Set this clock to this [`Duration`]
+    pub fn set_time(&self, time: Duration) {
+        self.time.set(time);
+    }
+
+    /// Advance this clock by this [`Duration`]
+    pub fn advance(&self, time: Duration) {
+        self.time.set(self.time.get() + time);
+    }
+
+    /// Get the current time of this clock
+    pub fn time(&self) -> Duration {
+        self.time.get()
+    }
+
+    /// Get an [`IsolatedInstant`] at this clock's current time
+    pub fn instant(&self) -> IsolatedInstant<'_> {
+        IsolatedInstant {
+            clock: self,
+            time: self.time(),
+        }
+    }
+}
+
+/// A deterministic instant, tied to the [`IsolatedClock`] which created it
+///
+/// Instants from different clocks are never equal, and have no ordering.
+#[derive(Debug, Copy, Clone)]
+pub struct IsolatedInstant<'a> {
+    clock: &'a IsolatedClock,
+    time: Duration,
+}
+
+impl IsolatedInstant<'_> {
+    /// Get the time of this instant, on its clock
+    pub const fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Get the time elapsed on this instant's clock since it was created
+    pub fn elapsed(&self) -> Duration {
+        self.clock.time().saturating_sub(self.time)
+    }
+
+    pub fn duration_since(&self, earlier: Self) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.time.checked_sub(earlier.time)
+    }
+}
+
+impl PartialEq for IsolatedInstant<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.clock, other.clock) && self.time == other.time
+    }
+}
+
+impl PartialOrd for IsolatedInstant<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        std::ptr::eq(self.clock, other.clock).then(|| self.time.cmp(&other.time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn independent_clocks() {
+        let a = IsolatedClock::new();
+        let b = IsolatedClock::new();
+
+        let start_a = a.instant();
+        let start_b = b.instant();
+
+        a.advance(Duration::from_secs(3));
+        b.advance(Duration::from_secs(30));
+        a.advance(Duration::from_secs(1));
+
+        assert_eq!(start_a.elapsed(), Duration::from_secs(4));
+        assert_eq!(start_b.elapsed(), Duration::from_secs(30));
+        assert_eq!(a.instant().duration_since(start_a), Duration::from_secs(4));
+
+        b.set_time(Duration::ZERO);
+        assert_eq!(start_b.elapsed(), Duration::ZERO);
+        assert_eq!(a.time(), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn instants_compare_on_the_same_clock() {
+        let a = IsolatedClock::new();
+        let b = IsolatedClock::new();
+
+        let first = a.instant();
+        a.advance(Duration::from_secs(1));
+        let second = a.instant();
+
+        assert!(first < second);
+        assert_eq!(first, first);
+        assert_ne!(a.instant(), b.instant());
+        assert_eq!(first.partial_cmp(&b.instant()), None);
+    }
+}
@@ -81,6 +81,24 @@ You can also get the current frozen time with `MockClock::time`
 
 `SystemTime` is also mockable with a similar API.
 
+Arithmetic on `Instant` and `SystemTime` keeps the full nanosecond precision of `Duration`:
+
+```rust
+use mock_instant::global::{MockClock, Instant};
+use std::time::Duration;
+
+MockClock::set_time(Duration::new(1, 999_999_999));
+let earlier = Instant::now();
+MockClock::advance(Duration::from_nanos(1));
+
+let later = Instant::now();
+assert_eq!(later.checked_duration_since(earlier), Some(Duration::from_nanos(1)));
+assert_eq!(earlier.checked_duration_since(later), None);
+
+assert_eq!(earlier + Duration::from_nanos(1), later);
+assert_eq!(later.checked_sub(Duration::from_nanos(1)), Some(earlier));
+```
+
 
 ## Thread-safety:
 
@@ -100,6 +118,51 @@ Two modes are provided via modules. The APIs are identical but the `MockClock` s
 
 use std::time::Duration;
 
+/// Assert that exactly `expected` has elapsed since `instant`, an `Instant` from either clock module
+///
+/// On failure, this reports the instant's offset from the clock's start and the clock's current time, along with
+/// the expected and actual elapsed time. An instant which is later than the clock fails, rather than panicking on
+/// the subtraction like `Instant::elapsed` does.
+///
+/// ```rust
+/// use mock_instant::global::{MockClock, Instant};
+/// use std::time::Duration;
+///
+/// let start = Instant::now();
+/// MockClock::advance(Duration::from_secs(3));
+/// mock_instant::assert_elapsed!(start, Duration::from_secs(3));
+/// ```
+#[macro_export]
+macro_rules! assert_elapsed {
+    ($instant:expr, $expected:expr $(,)?) => {{
+        let instant = $instant;
+        let expected: ::std::time::Duration = $expected;
+        // the current time of the clock the instant came from
+        let clock = if instant.is_thread_local() {
+            $crate::thread_local::MockClock::time()
+        } else {
+            $crate::global::MockClock::time()
+        };
+        match clock.checked_sub(instant.into_inner()) {
+            ::std::option::Option::Some(actual) if actual == expected => {}
+            ::std::option::Option::Some(actual) => ::std::panic!(
+                "expected {:?} to have elapsed, but {:?} has elapsed (the instant is at {:?}, the clock is at {:?})",
+                expected,
+                actual,
+                instant.into_inner(),
+                clock,
+            ),
+            ::std::option::Option::None => ::std::panic!(
+                "expected {:?} to have elapsed, but the instant is {:?} in the future (the instant is at {:?}, the clock is at {:?})",
+                expected,
+                instant.into_inner() - clock,
+                instant.into_inner(),
+                clock,
+            ),
+        }
+    }};
+}
+
 mod macros;
 
 /// An error returned from the duration_since and elapsed methods on SystemTime, used to learn how far in the opposite direction a system time lies.
@@ -125,12 +188,260 @@ impl std::error::Error for SystemTimeError {
     }
 }
 
+/// A panic from overflowing clock arithmetic, caught by `MockClock::catch_arithmetic`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArithmeticPanic {
+    message: String,
+}
+
+impl ArithmeticPanic {
+    // the arithmetic panics from `Duration`, `Instant` and `SystemTime` all start like this
+    pub(crate) fn from_payload(payload: &(dyn std::any::Any + Send)) -> Option<Self> {
+        let message = payload
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().copied())?;
+        message.starts_with("overflow when").then(|| Self {
+            message: message.to_string(),
+        })
+    }
+
+    /// The message the arithmetic panicked with, like `"overflow when adding duration to instant"`
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ArithmeticPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ArithmeticPanic {}
+
+// the environment variable read by `MockClock::init_from_env`
+pub(crate) const START_MS_VAR: &str = "MOCK_INSTANT_START_MS";
+
+// splits a duration into (hours, minutes, seconds, millis)
+pub(crate) fn hms(d: Duration) -> (u64, u64, u64, u32) {
+    let secs = d.as_secs();
+    (secs / 3600, secs / 60 % 60, secs % 60, d.subsec_millis())
+}
+
+// floors a duration to a multiple of the resolution, a zero resolution leaves it untouched
+pub(crate) fn quantize(d: Duration, resolution: Duration) -> Duration {
+    if resolution.is_zero() {
+        return d;
+    }
+    let resolution = resolution.as_nanos();
+    let nanos = d.as_nanos() / resolution * resolution;
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+// the real baseline used by `MockClock::set_time_to_process_uptime`, captured when the `Instant` clock is first used
+pub(crate) fn process_start() -> std::time::Instant {
+    static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    *START.get_or_init(std::time::Instant::now)
+}
+
+// the next (nanos, sequence) token after `last`, for a clock reading of `now` nanoseconds
+pub(crate) fn next_token(last: Option<(u128, u64)>, now: u128) -> (u128, u64) {
+    match last {
+        Some((nanos, sequence)) if now <= nanos => match sequence.checked_add(1) {
+            Some(sequence) => (nanos, sequence),
+            None => (nanos + 1, 0),
+        },
+        _ => (now, 0),
+    }
+}
+
+// the next value of a splitmix64 generator, a small seeded PRNG
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// makes a clock follow real time, scaled by a factor
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Realtime {
+    start: std::time::Instant,
+    factor: f64,
+}
+
+impl Realtime {
+    pub(crate) fn new(factor: f64) -> Self {
+        assert!(
+            factor.is_finite() && factor >= 0.0,
+            "realtime factor must be finite and non-negative, got {factor}"
+        );
+        Self {
+            start: std::time::Instant::now(),
+            factor,
+        }
+    }
+
+    pub(crate) const fn factor(&self) -> f64 {
+        self.factor
+    }
+
+    // the scaled real time since this started
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.start.elapsed().mul_f64(self.factor)
+    }
+
+    // the scaled real time since this started, then start again from now
+    pub(crate) fn restart(&mut self) -> Duration {
+        let elapsed = self.elapsed();
+        *self = Self::new(self.factor);
+        elapsed
+    }
+}
+
+/// The clock a [`ClockOp`] was applied to
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClockKind {
+    /// The clock backing `Instant`
+    Instant,
+    /// The clock backing `SystemTime`
+    SystemTime,
+}
+
+/// A mutation applied to a `MockClock`, as returned by `MockClock::last_op`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClockOp {
+    /// The clock was set to this [`Duration`]
+    Set(ClockKind, Duration),
+    /// The clock was advanced by this [`Duration`]
+    Advance(ClockKind, Duration),
+}
+
+/// What subtracting from an `Instant` does when the result would be below the floor set by `MockClock::set_floor`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FloorMode {
+    /// The result is clamped to the floor
+    Clamp,
+    /// The subtraction fails, `checked_sub` returns `None` and `-` panics
+    #[default]
+    Error,
+}
+
+/// A marker type for how a `MockClock` shares its state, either `global::GlobalMode` or `thread_local::ThreadLocalMode`
+pub trait ClockMode: Copy + Default + std::fmt::Debug {
+    /// Does a `MockClock` in this mode have a new state per thread?
+    const IS_THREAD_LOCAL: bool;
+}
+
+/// A `MockClock` with a [`ClockMode`], so generic code can require a specific mode with a bound like
+/// `C: HasClockMode<Mode = GlobalMode>`
+pub trait HasClockMode {
+    /// The mode of this clock
+    type Mode: ClockMode;
+}
+
+/// Both clocks of a `MockClock` at one moment, as returned by `MockClock::snapshot`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ClockSnapshot {
+    time: Duration,
+    system_time: Duration,
+}
+
+impl ClockSnapshot {
+    /// The `Instant` clock's time when this was taken
+    pub const fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// The `SystemTime` clock's time when this was taken
+    pub const fn system_time(&self) -> Duration {
+        self.system_time
+    }
+
+    /// Get how far each clock moved from this snapshot to `other`, in signed nanoseconds
+    ///
+    /// This returns `(time, system_time)`, which are negative if a clock is earlier in `other`
+    pub fn diff(&self, other: &ClockSnapshot) -> (i128, i128) {
+        let diff = |a: Duration, b: Duration| b.as_nanos() as i128 - a.as_nanos() as i128;
+        (
+            diff(self.time, other.time),
+            diff(self.system_time, other.system_time),
+        )
+    }
+
+    /// Encode this as the nanoseconds of each clock, as big-endian `u64`s, `time` first
+    ///
+    /// Times past `u64::MAX` nanoseconds (about 584 years) saturate to that.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let nanos = |d: Duration| {
+            u64::try_from(d.as_nanos())
+                .unwrap_or(u64::MAX)
+                .to_be_bytes()
+        };
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&nanos(self.time));
+        bytes[8..].copy_from_slice(&nanos(self.system_time));
+        bytes
+    }
+
+    /// Decode a snapshot encoded by [`ClockSnapshot::to_bytes`]
+    pub fn from_bytes(bytes: &[u8; 16]) -> Self {
+        let (time, system_time) = bytes.split_at(8);
+        let nanos = |b: &[u8]| Duration::from_nanos(u64::from_be_bytes(b.try_into().unwrap()));
+        Self {
+            time: nanos(time),
+            system_time: nanos(system_time),
+        }
+    }
+}
+
+// the global clock is shared by every test, so tests which touch it are run one at a time
+#[cfg(test)]
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    LOCK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
 /// Thread-local state.
 ///
 /// This creates a new state when accessed from a new thread
+///
+/// With the `strict` feature, debug builds panic when a spawned thread's first use of its clock is an advance, before
+/// it sets the clock or takes a `now()`, as that usually means the spawning thread's clock was expected to be shared.
+/// Named threads, like the main thread, are taken to use their clock on their own.
 pub mod thread_local;
 
 /// Global state.
 ///
 /// This shares its 'clock' across threads
 pub mod global;
+
+/// Isolated state.
+///
+/// This keeps its 'clock' in a value, rather than sharing it between threads or within a thread
+pub mod isolated;
+
+/// Configurable state.
+///
+/// This keeps its 'clock' and its configuration in a value, built at runtime
+pub mod dynamic;
+
+/// Wall-clock only state.
+///
+/// This only provides [`SystemTime`](system_only::SystemTime), sharing its 'clock' with [`global`]. There is no `Instant` here:
+///
+/// ```rust,compile_fail
+/// use mock_instant::system_only::Instant;
+/// ```
+pub mod system_only;
+
+/// Helpers for working with durations and instants in assertions.
+pub mod util;
+
+/// Helpers for analyzing timing data collected with the mocked clocks.
+pub mod stats;
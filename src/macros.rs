@@ -15,35 +15,641 @@ macro_rules! define_mock_clock {
             }
         }
 
+        /// A source of [`Instant`]s and [`SystemTime`]s, so a clock can be passed around, or stored as a `Box<dyn Clock>`
+        pub trait Clock {
+            /// Get the current [`Instant`]
+            fn now(&self) -> Instant;
+
+            /// Get the current [`SystemTime`]
+            fn system_now(&self) -> SystemTime;
+        }
+
+        impl Clock for MockClock {
+            fn now(&self) -> Instant {
+                Instant::now()
+            }
+
+            fn system_now(&self) -> SystemTime {
+                SystemTime::now()
+            }
+        }
+
+        /// Checks, when dropped, that the [`Instant`] clock advanced by an exact amount, created by [`MockClock::expect_advance`]
+        #[derive(Debug)]
+        #[must_use = "the check happens when this is dropped"]
+        pub struct ExpectAdvance {
+            start: Duration,
+            total: Duration,
+        }
+
+        impl Drop for ExpectAdvance {
+            fn drop(&mut self) {
+                // don't turn an existing panic into an abort
+                if std::thread::panicking() {
+                    return;
+                }
+
+                let now = self::get_time();
+                assert!(
+                    now.checked_sub(self.start) == Some(self.total),
+                    "expected the clock to advance by {:?}, but it went from {:?} to {:?}",
+                    self.total,
+                    self.start,
+                    now
+                );
+            }
+        }
+
         impl MockClock {
             /// Set the internal [`Instant`] clock to this [`Duration`]
             pub fn set_time(time: Duration) {
                 self::with_time(|t| *t = time);
+                self::set_last_op($crate::ClockOp::Set($crate::ClockKind::Instant, time));
             }
 
             /// Advance the internal [`Instant`] clock by this [`Duration`]
             pub fn advance(time: Duration) {
-                self::with_time(|t| *t += time);
+                Self::advance_then_now(time);
+            }
+
+            /// Advance the internal [`Instant`] clock by this [`Duration`], and get an [`Instant`] at the new time
+            pub fn advance_then_now(time: Duration) -> Instant {
+                let now = self::with_time(|t| {
+                    *t += time;
+                    *t
+                });
+                self::set_last_op($crate::ClockOp::Advance($crate::ClockKind::Instant, time));
+                self::run_advance_callbacks(time);
+                Instant($crate::quantize(now, self::get_resolution().0))
+            }
+
+            /// Call `f` with the amount the [`Instant`] clock is advanced by, each time [`MockClock::advance`] is called
+            ///
+            /// Once `f` returns [`ControlFlow::Break`](std::ops::ControlFlow::Break) it is removed and won't be called again.
+            pub fn on_advance_cancellable(
+                f: impl FnMut(Duration) -> std::ops::ControlFlow<()> + Send + 'static,
+            ) {
+                self::add_advance_callback(Box::new(f));
+            }
+
+            /// Advance the internal [`Instant`] clock up to `deadline`
+            ///
+            /// This does nothing if `deadline` has already passed, the clock never moves backwards.
+            pub fn advance_to(deadline: Instant) {
+                if let Some(remaining) = deadline.0.checked_sub(self::get_time()) {
+                    if !remaining.is_zero() {
+                        Self::advance(remaining);
+                    }
+                }
+            }
+
+            /// Advance the internal [`Instant`] clock to the next whole multiple of `period`
+            ///
+            /// This does nothing if the clock is already on a multiple of `period`, or if `period` is `Duration::ZERO`.
+            pub fn advance_to_next_period(period: Duration) {
+                let now = self::get_time();
+                let aligned = $crate::quantize(now, period);
+                if aligned != now {
+                    Self::advance(aligned + period - now);
+                }
+            }
+
+            /// Advance the [`Instant`] clock until at least `target` has elapsed since `instant`
+            ///
+            /// This does nothing if `target` has already elapsed.
+            pub fn advance_until_elapsed(instant: Instant, target: Duration) {
+                Self::advance_to(Instant(instant.0.saturating_add(target)));
+            }
+
+            /// The most steps [`MockClock::run_steps`] will take before giving up
+            pub const RUN_STEPS_LIMIT: usize = 1_000_000;
+
+            /// Repeatedly advance the [`Instant`] clock by `step`, calling `f` with an [`Instant::now`] after each advance
+            ///
+            /// This stops when `f` returns [`ControlFlow::Break`](std::ops::ControlFlow::Break), returning its value.
+            /// If `f` hasn't stopped after [`MockClock::RUN_STEPS_LIMIT`] steps, this gives up and returns `None`.
+            pub fn run_steps<T>(
+                step: Duration,
+                mut f: impl FnMut(Instant) -> std::ops::ControlFlow<T>,
+            ) -> Option<T> {
+                (0..Self::RUN_STEPS_LIMIT).find_map(|_| {
+                    Self::advance(step);
+                    f(Instant::now()).break_value()
+                })
+            }
+
+            /// Reset the internal [`Instant`] clock to `Duration::ZERO`, leaving the [`SystemTime`] clock alone
+            pub fn reset_time() {
+                Self::set_time(Duration::ZERO);
             }
 
             /// Get the current [`Instant`] duration
             pub fn time() -> Duration {
-                self::get_time()
+                $crate::quantize(self::get_time(), self::get_resolution().0)
+            }
+
+            /// Set the internal [`BootInstant`] clock to this [`Duration`]
+            pub fn set_boot_time(time: Duration) {
+                self::with_boot_time(|t| *t = time);
+            }
+
+            /// Advance the internal [`BootInstant`] clock by this [`Duration`]
+            ///
+            /// This clock is independent of the [`Instant`] clock, so it can model time (like a suspend)
+            /// which counts towards boot time but not towards monotonic time.
+            pub fn advance_boot_time(time: Duration) {
+                self::with_boot_time(|t| *t += time);
+            }
+
+            /// Get the current [`BootInstant`] duration
+            pub fn boot_time() -> Duration {
+                $crate::quantize(self::get_boot_time(), self::get_resolution().0)
             }
 
             /// Set the internal [`SystemTime`] clock to this [`Duration`]
             pub fn set_system_time(time: Duration) {
                 self::with_system_time(|t| *t = time);
+                self::set_last_op($crate::ClockOp::Set($crate::ClockKind::SystemTime, time));
             }
 
             /// Advance the internal [`SystemTime`] clock by this [`Duration`]
             pub fn advance_system_time(time: Duration) {
-                self::with_system_time(|t| *t += time);
+                Self::advance_system_then_now(time);
+            }
+
+            /// Advance the internal [`SystemTime`] clock by this [`Duration`], and get a [`SystemTime`] at the new time
+            pub fn advance_system_then_now(time: Duration) -> SystemTime {
+                let now = self::with_system_time(|t| {
+                    *t += time;
+                    *t
+                });
+                self::set_last_op($crate::ClockOp::Advance($crate::ClockKind::SystemTime, time));
+                SystemTime($crate::quantize(now, self::get_resolution().1))
+            }
+
+            /// Step the internal [`SystemTime`] clock backwards by this [`Duration`], saturating at [`UNIX_EPOCH`]
+            ///
+            /// This models a wall clock correction (like from NTP). Any [`SystemTime`] taken after the new time
+            /// will now return an error from [`SystemTime::elapsed`].
+            pub fn step_system_time_backward(by: Duration) {
+                Self::set_system_time(self::get_system_time().saturating_sub(by));
+            }
+
+            /// Reset the internal [`SystemTime`] clock to `Duration::ZERO`, leaving the [`Instant`] clock alone
+            pub fn reset_system_time() {
+                Self::set_system_time(Duration::ZERO);
+            }
+
+            /// Reset both clocks to `Duration::ZERO`, returning what they were as `(time, system_time)`
+            ///
+            /// The returned values can be passed to [`MockClock::set_time`] and [`MockClock::set_system_time`] to restore them.
+            pub fn reset_returning() -> (Duration, Duration) {
+                let previous = (self::get_time(), self::get_system_time());
+                Self::reset_time();
+                Self::reset_system_time();
+                previous
             }
 
             /// Get the current [`SystemTime`] duration
             pub fn system_time() -> Duration {
-                self::get_system_time()
+                let time = self::with_system_stall(|stall| {
+                    let (time, reads) = stall.as_mut()?;
+                    let time = *time;
+                    *reads -= 1;
+                    if *reads == 0 {
+                        *stall = None;
+                    }
+                    Some(time)
+                });
+                $crate::quantize(time.unwrap_or_else(self::get_system_time), self::get_resolution().1)
+            }
+
+            /// Make the next `for_reads` reads of the [`SystemTime`] clock return its current time, simulating a stall
+            ///
+            /// The clock itself keeps moving, so once the stall is over reads catch up with it. The [`Instant`] clock
+            /// isn't affected.
+            pub fn stall_system_time(for_reads: usize) {
+                let time = self::get_system_time();
+                self::with_system_stall(|stall| *stall = (for_reads > 0).then_some((time, for_reads)));
+            }
+
+            /// Floor every read of either clock to a multiple of this [`Duration`], simulating a coarse timer
+            ///
+            /// The clocks themselves keep full precision. A resolution of `Duration::ZERO` (the default) disables this.
+            /// To give each clock its own resolution, use [`MockClock::set_instant_resolution`] and
+            /// [`MockClock::set_system_time_resolution`].
+            pub fn set_resolution(resolution: Duration) {
+                self::with_resolution(|r| *r = (resolution, resolution));
+            }
+
+            /// Floor every read of the [`Instant`] (and [`BootInstant`]) clock to a multiple of this [`Duration`]
+            pub fn set_instant_resolution(resolution: Duration) {
+                self::with_resolution(|(instant, _)| *instant = resolution);
+            }
+
+            /// Floor every read of the [`SystemTime`] clock to a multiple of this [`Duration`]
+            pub fn set_system_time_resolution(resolution: Duration) {
+                self::with_resolution(|(_, system_time)| *system_time = resolution);
+            }
+
+            /// Get the resolution of the [`Instant`] clock, or `Duration::from_nanos(1)` if none is set
+            pub fn resolution() -> Duration {
+                match self::get_resolution().0 {
+                    Duration::ZERO => Duration::from_nanos(1),
+                    resolution => resolution,
+                }
+            }
+
+            /// Get the resolution of the [`SystemTime`] clock, or `Duration::from_nanos(1)` if none is set
+            pub fn system_time_resolution() -> Duration {
+                match self::get_resolution().1 {
+                    Duration::ZERO => Duration::from_nanos(1),
+                    resolution => resolution,
+                }
+            }
+
+            /// Set the [`SystemTime`] clock to `system` and the [`Instant`] clock to `instant`, together
+            ///
+            /// This returns what the clocks were before, as `(system, instant)`.
+            pub fn warp_to(system: Duration, instant: Duration) -> (Duration, Duration) {
+                let previous = self::with_clocks(|t, s| {
+                    (std::mem::replace(s, system), std::mem::replace(t, instant))
+                });
+                self::set_last_op($crate::ClockOp::Set($crate::ClockKind::Instant, instant));
+                previous
+            }
+
+            /// Set the frame rate, in frames per second, that [`MockClock::advance_frames`] uses. This is 60 by default
+            ///
+            /// # Panics
+            /// If `fps` isn't finite and positive
+            #[track_caller]
+            pub fn set_frame_rate(fps: f64) {
+                assert!(fps.is_finite() && fps > 0.0, "frame rate must be finite and positive, got {fps}");
+                self::set_frame_rate(fps);
+            }
+
+            /// Advance the [`Instant`] clock by `n` frames, at the rate set by [`MockClock::set_frame_rate`]
+            ///
+            /// The advance is rounded up to the nanosecond, so [`Instant::frame_number`] lands on the new frame.
+            pub fn advance_frames(n: u64) {
+                let nanos = (n as f64 * 1e9 / self::get_frame_rate()).ceil();
+                Self::advance(Duration::from_nanos(nanos as u64));
+            }
+
+            /// Pause the [`SystemTime`] clock, modelling a stopped real-time clock while the [`Instant`] clock keeps going
+            ///
+            /// While paused, [`MockClock::advance_everything`] and [`MockClock::resume_from_suspend`] don't move the
+            /// [`SystemTime`] clock. Setting or advancing it directly still works.
+            pub fn pause_system_time() {
+                self::set_system_paused(true);
+            }
+
+            /// Undo [`MockClock::pause_system_time`]
+            pub fn resume_system_time() {
+                self::set_system_paused(false);
+            }
+
+            /// Advance the [`Instant`], [`SystemTime`] and [`BootInstant`] clocks by this [`Duration`], together
+            ///
+            /// This models real time passing, which every clock sees. While the [`SystemTime`] clock is paused by
+            /// [`MockClock::pause_system_time`], it is left alone.
+            pub fn advance_everything(time: Duration) {
+                let system = if self::is_system_paused() { Duration::ZERO } else { time };
+                self::with_clocks(|t, s| {
+                    self::with_boot_time(|b| *b += time);
+                    *s += system;
+                    *t += time;
+                });
+                self::set_last_op($crate::ClockOp::Advance($crate::ClockKind::Instant, time));
+                self::run_advance_callbacks(time);
+            }
+
+            /// Advance the [`Instant`] clock `steps` times, each by a pseudo-random amount from `min` to `max` inclusive
+            ///
+            /// The amounts only depend on `seed`, so a walk can be reproduced. This returns the clock's time after each step.
+            ///
+            /// # Panics
+            /// If `min` is larger than `max`
+            #[track_caller]
+            pub fn random_walk(seed: u64, steps: usize, min: Duration, max: Duration) -> Vec<Duration> {
+                assert!(min <= max, "min ({min:?}) must not be larger than max ({max:?})");
+                let span = (max - min).as_nanos();
+                let mut state = seed;
+                (0..steps)
+                    .map(|_| {
+                        let offset = u128::from($crate::splitmix64(&mut state)) % (span + 1);
+                        Self::advance(min + Duration::from_nanos(offset as u64));
+                        self::get_time()
+                    })
+                    .collect()
+            }
+
+            /// Run `f`, turning a panic from overflowing clock arithmetic into an [`ArithmeticPanic`]($crate::ArithmeticPanic)
+            ///
+            /// Any other panic is passed on. The panic is still reported by the panic hook, as usual.
+            pub fn catch_arithmetic<R>(f: impl FnOnce() -> R) -> Result<R, $crate::ArithmeticPanic> {
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+                    match $crate::ArithmeticPanic::from_payload(&*payload) {
+                        Some(err) => err,
+                        None => std::panic::resume_unwind(payload),
+                    }
+                })
+            }
+
+            /// Advance both clocks by `wall_jump`, modelling a resume from suspend
+            pub fn resume_from_suspend(wall_jump: Duration) {
+                Self::resume_from_suspend_with_lag(wall_jump, Duration::ZERO);
+            }
+
+            /// Advance the [`SystemTime`] clock by `wall_jump` and the [`Instant`] clock by `wall_jump - lag`
+            ///
+            /// The [`Instant`] clock never moves backwards, a `lag` larger than `wall_jump` leaves it unchanged.
+            /// While the [`SystemTime`] clock is paused by [`MockClock::pause_system_time`], it is left alone.
+            pub fn resume_from_suspend_with_lag(wall_jump: Duration, lag: Duration) {
+                if !self::is_system_paused() {
+                    Self::advance_system_time(wall_jump);
+                }
+                Self::advance(wall_jump.saturating_sub(lag));
+            }
+
+            /// Get an [`Instant`] which is `after` from now, saturating rather than overflowing
+            pub fn deadline(after: Duration) -> Instant {
+                Instant(Instant::now().0.saturating_add(after))
+            }
+
+            /// Get a [`SystemTime`] which is `after` from now, saturating rather than overflowing
+            pub fn system_deadline(after: Duration) -> SystemTime {
+                SystemTime(SystemTime::now().0.saturating_add(after))
+            }
+
+            /// Assert that the [`Instant`] clock has moved away from `Duration::ZERO`
+            ///
+            /// This catches tests which forget to advance the clock, and so trivially pass at time zero.
+            ///
+            /// # Panics
+            /// If [`MockClock::time`] is `Duration::ZERO`
+            #[track_caller]
+            pub fn assert_advanced() {
+                assert!(
+                    !Self::time().is_zero(),
+                    "MockClock has not been advanced, it is still at Duration::ZERO"
+                );
+            }
+
+            /// Stop subtracting from an [`Instant`] from going below `floor`, modelling process start as a hard floor
+            ///
+            /// What happens instead is set by [`MockClock::set_floor_mode`]. The default floor is `Duration::ZERO`.
+            pub fn set_floor(floor: Duration) {
+                self::with_floor(|(f, _)| *f = floor);
+            }
+
+            /// Set what subtracting below the floor from [`MockClock::set_floor`] does, this is [`FloorMode::Error`]($crate::FloorMode::Error) by default
+            pub fn set_floor_mode(mode: $crate::FloorMode) {
+                self::with_floor(|(_, m)| *m = mode);
+            }
+
+            /// Expect the [`Instant`] clock to advance by exactly `total` before the returned guard is dropped
+            ///
+            /// # Panics
+            /// When the guard is dropped, if the clock isn't exactly `total` later than when this was called
+            pub fn expect_advance(total: Duration) -> ExpectAdvance {
+                ExpectAdvance {
+                    start: self::get_time(),
+                    total,
+                }
+            }
+
+            /// Set the [`Instant`] clock to each of these times in turn, calling `f` at each one, then restore the clock
+            ///
+            /// The clock is restored even if `f` panics.
+            pub fn for_each_time(times: &[Duration], mut f: impl FnMut()) {
+                struct Restore(Duration);
+                impl Drop for Restore {
+                    fn drop(&mut self) {
+                        MockClock::set_time(self.0);
+                    }
+                }
+
+                let _restore = Restore(self::get_time());
+                for &time in times {
+                    Self::set_time(time);
+                    f();
+                }
+            }
+
+            /// Set the [`Instant`] clock time that [`MockClock::uptime`] counts from, this is `Duration::ZERO` by default
+            pub fn set_process_start(start: Duration) {
+                self::set_process_start(start);
+            }
+
+            /// Get how long the [`Instant`] clock has run since the start set by [`MockClock::set_process_start`]
+            ///
+            /// This is `Duration::ZERO` if the clock is earlier than the start.
+            pub fn uptime() -> Duration {
+                Self::time().saturating_sub(self::get_process_start())
+            }
+
+            /// Set the [`Instant`] clock to the real time elapsed since this crate's process-wide baseline
+            ///
+            /// The baseline is a real [`std::time::Instant`], captured the first time the [`Instant`] clock is used in the process.
+            /// Real time that passed before that isn't counted.
+            pub fn set_time_to_process_uptime() {
+                Self::set_time($crate::process_start().elapsed());
+            }
+
+            /// Make the [`Instant`] clock follow real time, scaled by `factor`
+            ///
+            /// From now on the clock reads as its current time plus the real time since this call, multiplied by `factor`.
+            /// Setting or advancing the clock still works, and moves the baseline that real time is added to.
+            ///
+            /// # Panics
+            /// If `factor` is negative or not finite
+            pub fn set_realtime_scaled(factor: f64) {
+                self::set_realtime(Some($crate::Realtime::new(factor)));
+            }
+
+            /// Stop the [`Instant`] clock following real time, freezing it at its current time
+            pub fn stop_realtime() {
+                self::set_realtime(None);
+            }
+
+            /// Assert that the [`Instant`] and [`SystemTime`] clocks are within `tolerance` of each other
+            ///
+            /// # Panics
+            /// If `|time() - system_time()|` is larger than `tolerance`
+            #[track_caller]
+            pub fn assert_clocks_synced(tolerance: Duration) {
+                let (time, system_time) = (Self::time(), Self::system_time());
+                let diff = $crate::util::SignedDiff::new(time, system_time);
+                assert!(
+                    diff.magnitude() <= tolerance,
+                    "clocks are not synced within {tolerance:?}: time is {time:?}, system_time is {system_time:?} (difference: {diff})"
+                );
+            }
+
+            /// Assert that exactly `expected` has elapsed since both `instant` and `system`
+            ///
+            /// # Panics
+            /// If either [`Instant::elapsed`] or [`SystemTime::elapsed`] isn't `expected`
+            #[track_caller]
+            pub fn assert_both_elapsed(instant: Instant, system: SystemTime, expected: Duration) {
+                let elapsed = instant.elapsed();
+                let system_elapsed = system.elapsed();
+                assert!(
+                    elapsed == expected && system_elapsed.as_ref().is_ok_and(|&d| d == expected),
+                    "expected {expected:?} to have elapsed on both clocks, but {elapsed:?} has elapsed since {instant:?} and {system_elapsed:?} since {system:?}"
+                );
+            }
+
+            /// Assert that the [`SystemTime`] clock has advanced at least as much since `system_start` as the
+            /// [`Instant`] clock has since `instant_start`
+            ///
+            /// # Panics
+            /// If the [`Instant`] clock advanced more, or the [`SystemTime`] clock went backwards
+            #[track_caller]
+            pub fn assert_system_not_behind(instant_start: Instant, system_start: SystemTime) {
+                let elapsed = instant_start.elapsed();
+                match system_start.elapsed() {
+                    Ok(system_elapsed) => assert!(
+                        system_elapsed >= elapsed,
+                        "the system clock is behind: it advanced {system_elapsed:?} since {system_start:?}, but the monotonic clock advanced {elapsed:?} since {instant_start:?}"
+                    ),
+                    Err(err) => panic!(
+                        "the system clock is behind: it went back {:?} since {system_start:?}, but the monotonic clock advanced {elapsed:?} since {instant_start:?}",
+                        err.duration()
+                    ),
+                }
+            }
+
+            /// Capture the current time of both clocks
+            pub fn snapshot() -> $crate::ClockSnapshot {
+                $crate::ClockSnapshot {
+                    time: Self::time(),
+                    system_time: Self::system_time(),
+                }
+            }
+
+            /// Set both clocks from the `MOCK_INSTANT_START_MS` environment variable, in milliseconds
+            ///
+            /// This returns the time the clocks were set to, or `None` if the variable is unset.
+            /// If the variable can't be parsed, the clocks are left alone and the error is returned.
+            pub fn init_from_env() -> Result<Option<Duration>, std::num::ParseIntError> {
+                let Some(var) = std::env::var_os($crate::START_MS_VAR) else {
+                    return Ok(None);
+                };
+                let time = Duration::from_millis(var.to_string_lossy().trim().parse()?);
+                Self::set_time(time);
+                Self::set_system_time(time);
+                Ok(Some(time))
+            }
+
+            /// Get the factor set by [`MockClock::set_realtime_scaled`], or `None` if the clock isn't following real time
+            pub fn realtime_scale() -> Option<f64> {
+                self::get_realtime().map(|rt| rt.factor())
+            }
+
+            /// Get how far the [`Instant`] clock moves over `real` time, at the factor set by [`MockClock::set_realtime_scaled`]
+            ///
+            /// This is `real` multiplied by the factor, or `Duration::ZERO` if the clock isn't following real time.
+            pub fn simulated_for_real(real: Duration) -> Duration {
+                Self::realtime_scale().map_or(Duration::ZERO, |factor| real.mul_f64(factor))
+            }
+
+            /// Does the [`Instant`] clock only move when it is set or advanced?
+            ///
+            /// This is `false` while the clock is following real time, see [`MockClock::set_realtime_scaled`],
+            /// or while it is strictly monotonic, see [`MockClock::set_strictly_monotonic`].
+            pub fn is_frozen() -> bool {
+                self::get_realtime().is_none() && !self::is_strictly_monotonic()
+            }
+
+            /// Make every [`Instant::now`] advance the [`Instant`] clock by a nanosecond, so no two reads are equal
+            ///
+            /// This catches code which assumes `now()` always changes. Reads inside [`MockClock::with_frozen_now`]
+            /// don't advance the clock. A resolution set by [`MockClock::set_resolution`] still applies to the reads.
+            pub fn set_strictly_monotonic(enabled: bool) {
+                self::set_strictly_monotonic(enabled);
+            }
+
+            /// Run `f`, with every [`Instant::now`] and [`SystemTime::now`] on this thread returning the time from when this was called
+            ///
+            /// This models reading the clock once, then using that reading for the rest of `f`. Only `now()` is affected,
+            /// the clocks themselves still move. The previous behavior is restored when `f` returns or panics.
+            pub fn with_frozen_now<R>(f: impl FnOnce() -> R) -> R {
+                struct Restore(Option<(Duration, Duration)>);
+                impl Drop for Restore {
+                    fn drop(&mut self) {
+                        self::set_frozen_now(self.0);
+                    }
+                }
+
+                let _restore = Restore(self::get_frozen_now());
+                self::set_frozen_now(Some((Instant::now().0, SystemTime::now().0)));
+                f()
+            }
+
+            /// Follow real time scaled by `factor` while running `f`, then restore the previous mode
+            ///
+            /// The previous mode is restored even if `f` panics.
+            ///
+            /// # Panics
+            /// If `factor` is negative or not finite
+            pub fn with_speed<R>(factor: f64, f: impl FnOnce() -> R) -> R {
+                struct Restore(Option<f64>);
+                impl Drop for Restore {
+                    fn drop(&mut self) {
+                        self::set_realtime(self.0.map($crate::Realtime::new));
+                    }
+                }
+
+                let _restore = Restore(Self::realtime_scale());
+                Self::set_realtime_scaled(factor);
+                f()
+            }
+
+            /// Set the [`SystemTime`] clock to the real [`std::time::SystemTime::now`], and freeze it there
+            ///
+            /// The clock stays at this time until it is set or advanced again.
+            ///
+            /// # Panics
+            /// If the real system time is before [`std::time::UNIX_EPOCH`]
+            pub fn pause_at_real_now() {
+                Self::set_system_time(SystemTime::from(std::time::SystemTime::now()).0);
+            }
+
+            /// How many times have [`Instant::now`] and [`SystemTime::now`] been called, since the last reset?
+            pub fn now_call_count() -> u64 {
+                self::get_now_calls()
+            }
+
+            /// Reset the count returned by [`MockClock::now_call_count`] to zero
+            pub fn reset_now_call_count() {
+                self::reset_now_calls();
+            }
+
+            /// Get a token from the [`Instant`] clock which sorts after every token before it
+            ///
+            /// The token is the clock's time in nanoseconds and a sequence number, as fixed-width hex separated by a `-`.
+            /// The sequence number keeps tokens increasing while the clock is frozen, or if it moves backwards.
+            pub fn time_token() -> String {
+                let now = Self::time().as_nanos();
+                let (nanos, sequence) = self::with_last_token(|last| {
+                    let token = $crate::next_token(*last, now);
+                    *last = Some(token);
+                    token
+                });
+                format!("{nanos:032x}-{sequence:016x}")
+            }
+
+            /// Get this clock as a boxed [`Clock`] trait object
+            pub fn boxed() -> Box<dyn Clock + Send + Sync> {
+                Box::new(Self)
+            }
+
+            /// Get the most recent set or advance applied to either clock
+            pub fn last_op() -> Option<$crate::ClockOp> {
+                self::get_last_op()
             }
 
             /// Is this MockClock thread-local?
@@ -64,9 +670,26 @@ macro_rules! define_instant {
 
         impl Instant {
             pub fn now() -> Self {
+                self::count_now_call();
+                if let Some((time, _)) = self::get_frozen_now() {
+                    return Self(time);
+                }
+                if self::is_strictly_monotonic() {
+                    // bumped and read under the same lock, so concurrent reads can't see the same time
+                    let time = self::with_time(|t| {
+                        *t += Duration::from_nanos(1);
+                        *t
+                    });
+                    return Self($crate::quantize(time, self::get_resolution().0));
+                }
                 Self($now())
             }
 
+            /// Create an [`Instant`] at `d`, clamped so it is never later than `max`
+            pub fn bounded(d: Duration, max: Duration) -> Self {
+                Self(d.min(max))
+            }
+
             pub fn duration_since(&self, earlier: Self) -> Duration {
                 self.checked_duration_since(earlier).unwrap_or_default()
             }
@@ -83,20 +706,198 @@ macro_rules! define_instant {
                 $now() - self.0
             }
 
+            /// Get the [`Instant::elapsed`] time, capped at `max`
+            pub fn elapsed_capped(&self, max: Duration) -> Duration {
+                self.elapsed().min(max)
+            }
+
+            /// Get the [`Instant::elapsed`] time as `(hours, minutes, seconds, millis)`
+            pub fn elapsed_hms(&self) -> (u64, u64, u64, u32) {
+                $crate::hms(self.elapsed())
+            }
+
             pub fn checked_add(&self, duration: Duration) -> Option<Self> {
-                duration
-                    .as_millis()
-                    .checked_add(self.0.as_millis())
-                    .map(|c| Duration::from_millis(c as _))
-                    .map(Self)
+                self.0.checked_add(duration).map(Self)
             }
 
+            /// Subtract `duration` from this [`Instant`]
+            ///
+            /// If the result would be below the floor set by [`MockClock::set_floor`] (`Duration::ZERO` by default),
+            /// this returns `None` or clamps to the floor, depending on the [`FloorMode`]($crate::FloorMode).
+            /// Clamping never moves an [`Instant`] which is already below the floor.
             pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+                let (floor, mode) = self::get_floor();
+                match self.0.checked_sub(duration) {
+                    Some(d) if d >= floor => Some(Self(d)),
+                    _ if mode == $crate::FloorMode::Clamp => Some(Self(floor.min(self.0))),
+                    _ => None,
+                }
+            }
+
+            /// Does this [`Instant`] sit at the same offset as this [`SystemTime`]?
+            pub fn aligns_with(&self, st: SystemTime) -> bool {
+                self.0 == st.0
+            }
+
+            /// The signed difference, in nanoseconds, between this [`Instant`] and this [`SystemTime`]
+            pub fn offset_from(&self, st: SystemTime) -> i128 {
+                self.0.as_nanos() as i128 - st.0.as_nanos() as i128
+            }
+
+            /// Multiply this [`Instant`]'s offset from zero by `factor`, returning `None` on overflow
+            pub fn checked_mul_offset(&self, factor: u32) -> Option<Self> {
+                self.0.checked_mul(factor).map(Self)
+            }
+
+            /// Assert that this [`Instant`] is no more than `window` away from `other`, in either direction
+            ///
+            /// # Panics
+            /// If the difference between the two instants is larger than `window`
+            #[track_caller]
+            pub fn assert_within(&self, other: Self, window: Duration) {
+                let delta = self.0.abs_diff(other.0);
+                assert!(
+                    delta <= window,
+                    "{self:?} is not within {window:?} of {other:?} (difference: {delta:?})"
+                );
+            }
+
+            /// Get the weighted average of these instants, as `(instant, weight)` pairs
+            ///
+            /// Returns `None` if the weights sum to zero, or if the average isn't a valid [`Instant`]
+            pub fn weighted_mean(items: &[(Self, f64)]) -> Option<Self> {
+                let total: f64 = items.iter().map(|(_, weight)| weight).sum();
+                if total == 0.0 {
+                    return None;
+                }
+
+                let sum: f64 = items
+                    .iter()
+                    .map(|(instant, weight)| instant.0.as_secs_f64() * weight)
+                    .sum();
+                Duration::try_from_secs_f64(sum / total).ok().map(Self)
+            }
+
+            /// How many whole `step`s fit between this [`Instant`] and `deadline`?
+            ///
+            /// This is zero if `deadline` isn't later than this, or if `step` is `Duration::ZERO`.
+            /// The count saturates at `u64::MAX`.
+            pub fn steps_until(&self, deadline: Self, step: Duration) -> u64 {
+                if step.is_zero() {
+                    return 0;
+                }
+                let span = deadline.saturating_duration_since(*self);
+                u64::try_from(span.as_nanos() / step.as_nanos()).unwrap_or(u64::MAX)
+            }
+
+            /// Get the whole seconds since `earlier`, or zero if `earlier` is later than this
+            pub fn secs_since(&self, earlier: Self) -> u64 {
+                self.saturating_duration_since(earlier).as_secs()
+            }
+
+            /// Get the whole milliseconds since `earlier`, or zero if `earlier` is later than this
+            pub fn millis_since(&self, earlier: Self) -> u128 {
+                self.saturating_duration_since(earlier).as_millis()
+            }
+
+            /// Get the whole microseconds since `earlier`, or zero if `earlier` is later than this
+            pub fn micros_since(&self, earlier: Self) -> u128 {
+                self.saturating_duration_since(earlier).as_micros()
+            }
+
+            /// Get the whole nanoseconds since `earlier`, or zero if `earlier` is later than this
+            pub fn nanos_since(&self, earlier: Self) -> u128 {
+                self.saturating_duration_since(earlier).as_nanos()
+            }
+
+            /// Floor this [`Instant`] to a multiple of `resolution`, a `resolution` of `Duration::ZERO` leaves it unchanged
+            pub fn quantized(&self, resolution: Duration) -> Self {
+                Self($crate::quantize(self.0, resolution))
+            }
+
+            /// Assert that exactly `expected` has elapsed since this [`Instant`]
+            ///
+            /// # Panics
+            /// If [`Instant::elapsed`] isn't `expected`
+            #[track_caller]
+            pub fn assert_elapsed(&self, expected: Duration) {
+                let actual = self.elapsed();
+                assert!(
+                    actual == expected,
+                    "expected {expected:?} to have elapsed since {self:?}, but {actual:?} has elapsed"
+                );
+            }
+
+            /// How far this [`Instant`] is from `start` toward `start + total`, as a fraction from `0.0` to `1.0`
+            ///
+            /// This is clamped, so it is `0.0` before `start` and `1.0` from `start + total` on.
+            /// If `total` is `Duration::ZERO` this is `1.0` from `start` on.
+            pub fn progress_toward(&self, start: Self, total: Duration) -> f64 {
+                let elapsed = self.saturating_duration_since(start);
+                if total.is_zero() {
+                    return if *self < start { 0.0 } else { 1.0 };
+                }
+                (elapsed.as_secs_f64() / total.as_secs_f64()).min(1.0)
+            }
+
+            /// Which frame, at `fps` frames per second from the clock's start, this [`Instant`] falls in
+            pub fn frame_number(&self, fps: f64) -> u64 {
+                (self.0.as_secs_f64() * fps).floor() as u64
+            }
+
+            /// Get how much time has elapsed since this [`Instant`], for checking around an `.await`
+            ///
+            /// Like [`Instant::elapsed`], it only depends on how the mocked clock was moved while the task was
+            /// suspended, not on how the task was scheduled. It is `Duration::ZERO` if the clock was moved back
+            /// before this [`Instant`].
+            ///
+            /// ```rust
+            /// # use mock_instant::global::{MockClock, Instant};
+            /// # use std::{future::Future, pin::{pin, Pin}, task::{Context, Poll, Waker}, time::Duration};
+            /// // pending on its first poll, like a future waiting on io
+            /// struct YieldOnce(bool);
+            /// impl Future for YieldOnce {
+            ///     type Output = ();
+            ///     fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            ///         if std::mem::replace(&mut self.0, true) { Poll::Ready(()) } else { Poll::Pending }
+            ///     }
+            /// }
+            ///
+            /// MockClock::set_time(Duration::ZERO);
+            /// let mut task = pin!(async {
+            ///     let start = Instant::now();
+            ///     YieldOnce(false).await;
+            ///     start.checkpoint()
+            /// });
+            ///
+            /// let mut cx = Context::from_waker(Waker::noop());
+            /// assert!(task.as_mut().poll(&mut cx).is_pending());
+            ///
+            /// // the test moves the clock while the task is suspended
+            /// MockClock::advance(Duration::from_secs(2));
+            /// assert_eq!(task.as_mut().poll(&mut cx), Poll::Ready(Duration::from_secs(2)));
+            /// ```
+            pub fn checkpoint(&self) -> Duration {
+                Instant::now().saturating_duration_since(*self)
+            }
+
+            /// Get an exponential backoff, `base * factor^attempts` capped at `max`
+            ///
+            /// `attempts` is how many whole `base` intervals have elapsed since this [`Instant`].
+            pub fn backoff_since(&self, base: Duration, factor: f64, max: Duration) -> Duration {
+                let attempts = match base.as_nanos() {
+                    0 => 0,
+                    base => (self.elapsed().as_nanos() / base).min(i32::MAX as u128) as i32,
+                };
+                Duration::try_from_secs_f64(base.as_secs_f64() * factor.powi(attempts))
+                    .map_or(max, |backoff| backoff.min(max))
+            }
+
+            /// Get the [`Duration`] this [`Instant`] wraps, its offset from the clock's start
+            ///
+            /// This is the way to compare an [`Instant`] against an expected [`Duration`].
+            pub const fn into_inner(self) -> Duration {
                 self.0
-                    .as_millis()
-                    .checked_sub(duration.as_millis())
-                    .map(|c| Duration::from_millis(c as _))
-                    .map(Self)
             }
 
             /// Is this Instant thread-local?
@@ -135,79 +936,357 @@ macro_rules! define_instant {
 
         impl std::ops::SubAssign<Duration> for Instant {
             fn sub_assign(&mut self, rhs: Duration) {
-                self.0 -= rhs
+                *self = *self - rhs
             }
         }
-    };
-}
-
-macro_rules! define_system_time {
-    ($now:expr; $thread_local:expr; $(#[$outer:meta])*) => {
-        /// A simple deterministic [`std::time::SystemTime`] wrapped around a modifiable [`std::time::Duration`]
-        ///
-        /// The source is the [`MockClock`]
-        ///
-        $(#[$outer])*
-        #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
-        pub struct SystemTime(Duration);
-
-        /// A mocked UNIX_EPOCH. This starts at `0` rather than the traditional unix epoch date.
-        pub const UNIX_EPOCH: SystemTime = SystemTime(Duration::ZERO);
 
-        impl SystemTime {
-            pub const UNIX_EPOCH: SystemTime = UNIX_EPOCH;
+        /// Create an [`Instant`] at this offset from the clock's start
+        impl From<Duration> for Instant {
+            fn from(value: Duration) -> Self {
+                Self(value)
+            }
+        }
 
-            pub fn now() -> Self {
-                Self($now())
+        /// Create an [`Instant`] from `(secs, nanos)`, as with [`Duration::new`]
+        impl From<(u64, u32)> for Instant {
+            fn from((secs, nanos): (u64, u32)) -> Self {
+                Self(Duration::new(secs, nanos))
             }
+        }
 
-            pub fn duration_since(
-                &self,
-                earlier: SystemTime,
-            ) -> Result<Duration, $crate::SystemTimeError> {
-                self.0
-                    .checked_sub(earlier.0)
-                    .ok_or_else(|| $crate::SystemTimeError(earlier.0 - self.0))
+        /// An [`Instant`] compares to a [`Duration`] by its offset from the clock's start at `Duration::ZERO`
+        ///
+        /// So `Instant::now() < Duration::from_secs(5)` is true until the clock reaches 5 seconds.
+        /// To compare against the time since an [`Instant`], use [`Instant::elapsed`].
+        impl PartialEq<Duration> for Instant {
+            fn eq(&self, other: &Duration) -> bool {
+                self.0 == *other
             }
+        }
 
-            pub fn elapsed(&self) -> Result<Duration, $crate::SystemTimeError> {
-                Self::now().duration_since(*self)
+        /// Compares by the offset from the clock's start, the same as `PartialEq<Duration>`
+        impl PartialOrd<Duration> for Instant {
+            fn partial_cmp(&self, other: &Duration) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(other)
             }
+        }
 
-            pub fn checked_add(&self, duration: Duration) -> Option<SystemTime> {
-                duration
-                    .as_millis()
-                    .checked_add(self.0.as_millis())
-                    .map(|c| Duration::from_millis(c as _))
-                    .map(Self)
+        /// An [`Instant`] paired with a sequence number, for use as a key in ordered collections
+        ///
+        /// These order by their instant, and then by the order they were created in. So equal instants don't
+        /// collapse in a `BTreeSet`, and iterate in the order they were inserted.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct OrderedInstant {
+            instant: Instant,
+            sequence: u64,
+        }
+
+        impl OrderedInstant {
+            /// Wrap this [`Instant`], ordering it after every `OrderedInstant` created before it
+            pub fn new(instant: Instant) -> Self {
+                static SEQUENCE: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+                Self {
+                    instant,
+                    sequence: SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+                }
             }
 
-            pub fn checked_sub(&self, duration: Duration) -> Option<SystemTime> {
-                self.0
-                    .as_millis()
-                    .checked_sub(duration.as_millis())
-                    .map(|c| Duration::from_millis(c as _))
-                    .map(Self)
+            /// Get the wrapped [`Instant`]
+            pub const fn instant(&self) -> Instant {
+                self.instant
             }
 
-            /// Is this SystemTime thread-local?
-            pub const fn is_thread_local(&self) -> bool {
-                $thread_local
+            /// Get the sequence number this was created with
+            pub const fn sequence(&self) -> u64 {
+                self.sequence
             }
         }
 
-        impl std::ops::Add<Duration> for SystemTime {
-            type Output = SystemTime;
+        /// A half-open range of [`Instant`]s, from `start` up to but not including `end`
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub struct Range {
+            /// The first instant in the range
+            pub start: Instant,
+            /// The instant just after the range
+            pub end: Instant,
+        }
 
-            fn add(self, rhs: Duration) -> Self::Output {
-                self.checked_add(rhs)
-                    .expect("overflow when adding duration to instant")
+        impl Range {
+            /// Do these ranges share any instant?
+            ///
+            /// Ranges which only touch, where one ends as the other starts, don't overlap.
+            pub fn overlaps(&self, other: &Range) -> bool {
+                self.start < other.end && other.start < self.end
             }
-        }
 
-        impl std::ops::AddAssign<Duration> for SystemTime {
-            fn add_assign(&mut self, rhs: Duration) {
-                *self = *self + rhs
+            /// Get the range of instants in both of these ranges, if there are any
+            pub fn intersection(&self, other: &Range) -> Option<Range> {
+                let range = Range {
+                    start: self.start.max(other.start),
+                    end: self.end.min(other.end),
+                };
+                (range.start < range.end).then_some(range)
+            }
+        }
+
+        /// A budget of time, used up as the [`Instant`] clock advances
+        ///
+        /// This is useful for driving retry loops, like `while !budget.is_exhausted() { .. }`
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub struct Budget {
+            start: Instant,
+            total: Duration,
+        }
+
+        impl Budget {
+            /// Start a budget of `total`, from [`Instant::now`]
+            pub fn new(total: Duration) -> Self {
+                Self {
+                    start: Instant::now(),
+                    total,
+                }
+            }
+
+            /// How much of the budget is left?
+            ///
+            /// This is `Duration::ZERO` once the budget is exhausted, and all of it while the clock is earlier than its start.
+            pub fn remaining(&self) -> Duration {
+                let used = Instant::now().saturating_duration_since(self.start);
+                self.total.saturating_sub(used)
+            }
+
+            /// Has all of the budget been used up?
+            pub fn is_exhausted(&self) -> bool {
+                self.remaining().is_zero()
+            }
+        }
+
+        /// Pairs the items of an [`Iterator`] with the [`Instant`] they were produced at
+        pub trait TimestampExt: Iterator + Sized {
+            /// Pair each item with an [`Instant::now`], read just after the item is produced
+            fn timestamped(self) -> Timestamped<Self> {
+                Timestamped { iter: self }
+            }
+        }
+
+        impl<I: Iterator> TimestampExt for I {}
+
+        /// An [`Iterator`] yielding `(Instant, item)` pairs, created by [`TimestampExt::timestamped`]
+        #[derive(Debug, Clone)]
+        pub struct Timestamped<I> {
+            iter: I,
+        }
+
+        impl<I: Iterator> Iterator for Timestamped<I> {
+            type Item = (Instant, I::Item);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let item = self.iter.next()?;
+                Some((Instant::now(), item))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+    };
+}
+
+macro_rules! define_system_time {
+    ($now:expr; $thread_local:expr; $(#[$outer:meta])*) => {
+        /// A simple deterministic [`std::time::SystemTime`] wrapped around a modifiable [`std::time::Duration`]
+        ///
+        /// The source is the [`MockClock`]
+        ///
+        $(#[$outer])*
+        #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+        pub struct SystemTime(Duration);
+
+        /// A mocked UNIX_EPOCH. This starts at `0` rather than the traditional unix epoch date.
+        pub const UNIX_EPOCH: SystemTime = SystemTime(Duration::ZERO);
+
+        impl SystemTime {
+            pub const UNIX_EPOCH: SystemTime = UNIX_EPOCH;
+
+            pub fn now() -> Self {
+                self::count_now_call();
+                Self(self::get_frozen_now().map_or_else(|| $now(), |(_, system_time)| system_time))
+            }
+
+            pub fn duration_since(
+                &self,
+                earlier: SystemTime,
+            ) -> Result<Duration, $crate::SystemTimeError> {
+                self.0
+                    .checked_sub(earlier.0)
+                    .ok_or_else(|| $crate::SystemTimeError(earlier.0 - self.0))
+            }
+
+            pub fn elapsed(&self) -> Result<Duration, $crate::SystemTimeError> {
+                Self::now().duration_since(*self)
+            }
+
+            /// Get the whole seconds since `earlier`, failing as [`SystemTime::duration_since`] does
+            pub fn secs_since(&self, earlier: SystemTime) -> Result<u64, $crate::SystemTimeError> {
+                self.duration_since(earlier).map(|d| d.as_secs())
+            }
+
+            /// Get the whole milliseconds since `earlier`, failing as [`SystemTime::duration_since`] does
+            pub fn millis_since(&self, earlier: SystemTime) -> Result<u128, $crate::SystemTimeError> {
+                self.duration_since(earlier).map(|d| d.as_millis())
+            }
+
+            /// Get the whole microseconds since `earlier`, failing as [`SystemTime::duration_since`] does
+            pub fn micros_since(&self, earlier: SystemTime) -> Result<u128, $crate::SystemTimeError> {
+                self.duration_since(earlier).map(|d| d.as_micros())
+            }
+
+            /// Get the whole nanoseconds since `earlier`, failing as [`SystemTime::duration_since`] does
+            pub fn nanos_since(&self, earlier: SystemTime) -> Result<u128, $crate::SystemTimeError> {
+                self.duration_since(earlier).map(|d| d.as_nanos())
+            }
+
+            /// Get the [`SystemTime::elapsed`] time, or `Duration::ZERO` if the clock is now earlier than this
+            pub fn elapsed_or_zero(&self) -> Duration {
+                self.elapsed().unwrap_or_default()
+            }
+
+            /// Get the [`SystemTime::elapsed`] time, capped at `max`
+            pub fn elapsed_capped(&self, max: Duration) -> Result<Duration, $crate::SystemTimeError> {
+                self.elapsed().map(|elapsed| elapsed.min(max))
+            }
+
+            /// Get the [`SystemTime::elapsed`] time as `(hours, minutes, seconds, millis)`
+            pub fn elapsed_hms(&self) -> Result<(u64, u64, u64, u32), $crate::SystemTimeError> {
+                self.elapsed().map($crate::hms)
+            }
+
+            pub fn checked_add(&self, duration: Duration) -> Option<SystemTime> {
+                self.0.checked_add(duration).map(Self)
+            }
+
+            pub fn checked_sub(&self, duration: Duration) -> Option<SystemTime> {
+                self.0.checked_sub(duration).map(Self)
+            }
+
+            /// Multiply this [`SystemTime`]'s offset from [`UNIX_EPOCH`] by `factor`, returning `None` on overflow
+            pub fn checked_mul_offset(&self, factor: u32) -> Option<SystemTime> {
+                self.0.checked_mul(factor).map(Self)
+            }
+
+            /// Get this [`SystemTime`] plus `secs` seconds, saturating rather than overflowing
+            pub const fn plus_secs(self, secs: u64) -> Self {
+                Self(self.0.saturating_add(Duration::from_secs(secs)))
+            }
+
+            /// Get this [`SystemTime`] plus `millis` milliseconds, saturating rather than overflowing
+            pub const fn plus_millis(self, millis: u64) -> Self {
+                Self(self.0.saturating_add(Duration::from_millis(millis)))
+            }
+
+            /// Get this [`SystemTime`] plus `micros` microseconds, saturating rather than overflowing
+            pub const fn plus_micros(self, micros: u64) -> Self {
+                Self(self.0.saturating_add(Duration::from_micros(micros)))
+            }
+
+            /// Get this [`SystemTime`] plus `nanos` nanoseconds, saturating rather than overflowing
+            pub const fn plus_nanos(self, nanos: u64) -> Self {
+                Self(self.0.saturating_add(Duration::from_nanos(nanos)))
+            }
+
+            /// Get this [`SystemTime`] minus `secs` seconds, saturating at [`UNIX_EPOCH`]
+            pub const fn minus_secs(self, secs: u64) -> Self {
+                Self(self.0.saturating_sub(Duration::from_secs(secs)))
+            }
+
+            /// Get this [`SystemTime`] minus `millis` milliseconds, saturating at [`UNIX_EPOCH`]
+            pub const fn minus_millis(self, millis: u64) -> Self {
+                Self(self.0.saturating_sub(Duration::from_millis(millis)))
+            }
+
+            /// Get this [`SystemTime`] minus `micros` microseconds, saturating at [`UNIX_EPOCH`]
+            pub const fn minus_micros(self, micros: u64) -> Self {
+                Self(self.0.saturating_sub(Duration::from_micros(micros)))
+            }
+
+            /// Get this [`SystemTime`] minus `nanos` nanoseconds, saturating at [`UNIX_EPOCH`]
+            pub const fn minus_nanos(self, nanos: u64) -> Self {
+                Self(self.0.saturating_sub(Duration::from_nanos(nanos)))
+            }
+
+            /// Get the [`SystemTime`] halfway between this and `other`, rounded down to the nanosecond
+            ///
+            /// This can't overflow, even for times near the largest [`Duration`].
+            pub fn midpoint(&self, other: SystemTime) -> SystemTime {
+                let (low, high) = if self.0 <= other.0 { (self.0, other.0) } else { (other.0, self.0) };
+                Self(low + (high - low) / 2)
+            }
+
+            /// Is this [`SystemTime`] at least `max_age` old? A time in the future is never stale
+            pub fn is_stale(&self, max_age: Duration) -> bool {
+                SystemTime::now().0.checked_sub(self.0).is_some_and(|age| age >= max_age)
+            }
+
+            /// Would [`SystemTime::duration_since`] return an error for `earlier`? That is, is `earlier` later than this?
+            pub fn would_error_since(&self, earlier: SystemTime) -> bool {
+                earlier.0 > self.0
+            }
+
+            /// Get the first [`SystemTime`], from this one on, which is a whole multiple of `period` since [`UNIX_EPOCH`]
+            ///
+            /// A time which is already aligned is returned as is. A `period` of `Duration::ZERO` also returns this time.
+            /// Returns `None` if the next multiple can't be represented.
+            pub fn next_multiple_of(&self, period: Duration) -> Option<Self> {
+                let aligned = $crate::quantize(self.0, period);
+                if aligned == self.0 {
+                    return Some(*self);
+                }
+                aligned.checked_add(period).map(Self)
+            }
+
+            /// Assert that exactly `expected` has elapsed since this [`SystemTime`]
+            ///
+            /// # Panics
+            /// If [`SystemTime::elapsed`] isn't `expected`, or if the clock is earlier than this
+            #[track_caller]
+            pub fn assert_elapsed(&self, expected: Duration) {
+                match self.elapsed() {
+                    Ok(actual) => assert!(
+                        actual == expected,
+                        "expected {expected:?} to have elapsed since {self:?}, but {actual:?} has elapsed"
+                    ),
+                    Err(err) => panic!(
+                        "expected {expected:?} to have elapsed since {self:?}, but the clock is {:?} earlier",
+                        err.duration()
+                    ),
+                }
+            }
+
+            /// Get the [`Duration`] this [`SystemTime`] wraps, its offset from [`UNIX_EPOCH`]
+            ///
+            /// This is the way to compare a [`SystemTime`] against an expected [`Duration`].
+            pub const fn into_inner(self) -> Duration {
+                self.0
+            }
+
+            /// Is this SystemTime thread-local?
+            pub const fn is_thread_local(&self) -> bool {
+                $thread_local
+            }
+        }
+
+        impl std::ops::Add<Duration> for SystemTime {
+            type Output = SystemTime;
+
+            fn add(self, rhs: Duration) -> Self::Output {
+                self.checked_add(rhs)
+                    .expect("overflow when adding duration to instant")
+            }
+        }
+
+        impl std::ops::AddAssign<Duration> for SystemTime {
+            fn add_assign(&mut self, rhs: Duration) {
+                *self = *self + rhs
             }
         }
 
@@ -226,6 +1305,20 @@ macro_rules! define_system_time {
             }
         }
 
+        /// Create a [`SystemTime`] at this offset from [`UNIX_EPOCH`]
+        impl From<Duration> for SystemTime {
+            fn from(value: Duration) -> Self {
+                Self(value)
+            }
+        }
+
+        /// Create a [`SystemTime`] from `(secs, nanos)` since [`UNIX_EPOCH`], as with [`Duration::new`]
+        impl From<(u64, u32)> for SystemTime {
+            fn from((secs, nanos): (u64, u32)) -> Self {
+                Self(Duration::new(secs, nanos))
+            }
+        }
+
         impl From<std::time::SystemTime> for SystemTime {
             fn from(value: std::time::SystemTime) -> Self {
                 Self(
@@ -244,6 +1337,68 @@ macro_rules! define_system_time {
     };
 }
 
+macro_rules! define_boot_instant {
+    ($now:expr; $thread_local:expr; $(#[$outer:meta])*) => {
+        /// A deterministic boot-time clock reading (like `CLOCK_BOOTTIME`), wrapped around a modifiable [`std::time::Duration`]
+        ///
+        /// The source is the [`MockClock`]'s boot time, which moves independently of [`Instant`]
+        ///
+        $(#[$outer])*
+        #[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+        pub struct BootInstant(Duration);
+
+        impl BootInstant {
+            pub fn now() -> Self {
+                Self($now())
+            }
+
+            pub fn duration_since(&self, earlier: Self) -> Duration {
+                self.checked_duration_since(earlier).unwrap_or_default()
+            }
+
+            pub fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+                self.0.checked_sub(earlier.0)
+            }
+
+            pub fn saturating_duration_since(&self, earlier: Self) -> Duration {
+                self.checked_duration_since(earlier).unwrap_or_default()
+            }
+
+            pub fn elapsed(&self) -> Duration {
+                $now().saturating_sub(self.0)
+            }
+
+            pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+                self.0.checked_add(duration).map(Self)
+            }
+
+            pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+                self.0.checked_sub(duration).map(Self)
+            }
+
+            /// Is this BootInstant thread-local?
+            pub const fn is_thread_local(&self) -> bool {
+                $thread_local
+            }
+        }
+
+        impl std::ops::Add<Duration> for BootInstant {
+            type Output = Self;
+            fn add(self, rhs: Duration) -> Self::Output {
+                self.checked_add(rhs)
+                    .expect("overflow when adding duration to instant")
+            }
+        }
+
+        impl std::ops::Sub for BootInstant {
+            type Output = Duration;
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.duration_since(rhs)
+            }
+        }
+    };
+}
+
 macro_rules! define_instant_tests {
     () => {
         #[cfg(test)]
@@ -251,15 +1406,16 @@ macro_rules! define_instant_tests {
             use super::*;
 
             fn reset_time() {
-                MockClock::set_time(Duration::ZERO)
+                MockClock::reset_time()
             }
 
             fn reset_system_time() {
-                MockClock::set_system_time(Duration::ZERO)
+                MockClock::reset_system_time()
             }
 
             #[test]
             fn set_system_time() {
+                let _lock = crate::test_lock();
                 reset_system_time();
 
                 MockClock::set_system_time(Duration::from_secs(42));
@@ -271,6 +1427,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn advance_system_time() {
+                let _lock = crate::test_lock();
                 reset_system_time();
 
                 for i in 0..3 {
@@ -282,6 +1439,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn system_time() {
+                let _lock = crate::test_lock();
                 reset_system_time();
 
                 let now = SystemTime::now();
@@ -300,6 +1458,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn system_time_methods() {
+                let _lock = crate::test_lock();
                 reset_system_time();
 
                 let system_time = SystemTime::now();
@@ -343,8 +1502,81 @@ macro_rules! define_instant_tests {
                     .is_none());
             }
 
+            #[test]
+            fn system_time_elapsed_or_zero() {
+                let _lock = crate::test_lock();
+                MockClock::set_system_time(Duration::from_secs(10));
+
+                let system_time = SystemTime::now();
+                MockClock::advance_system_time(Duration::from_secs(2));
+                assert_eq!(system_time.elapsed_or_zero(), Duration::from_secs(2));
+
+                MockClock::set_system_time(Duration::from_secs(5));
+                assert!(system_time.elapsed().is_err());
+                assert_eq!(system_time.elapsed_or_zero(), Duration::ZERO);
+            }
+
+            #[test]
+            fn system_time_plus_minus() {
+                let _lock = crate::test_lock();
+
+                let time = SystemTime::UNIX_EPOCH
+                    .plus_secs(100)
+                    .plus_millis(500)
+                    .plus_micros(20)
+                    .plus_nanos(3);
+                assert_eq!(time, SystemTime(Duration::new(100, 500_020_003)));
+
+                let time = time
+                    .minus_secs(50)
+                    .minus_millis(500)
+                    .minus_micros(20)
+                    .minus_nanos(3);
+                assert_eq!(time, SystemTime(Duration::from_secs(50)));
+
+                assert_eq!(time.minus_secs(51), UNIX_EPOCH);
+                assert_eq!(UNIX_EPOCH.minus_nanos(1), UNIX_EPOCH);
+                assert_eq!(
+                    SystemTime(Duration::MAX).plus_nanos(1),
+                    SystemTime(Duration::MAX)
+                );
+            }
+
+            #[test]
+            fn pause_at_real_now() {
+                let _lock = crate::test_lock();
+
+                MockClock::pause_at_real_now();
+                let paused = SystemTime::now();
+                assert!(paused.0 > Duration::from_secs(1708041600)); // Friday 16 February 2024 00:00:00 GMT
+
+                std::thread::sleep(Duration::from_millis(5));
+                assert_eq!(SystemTime::now(), paused);
+                assert_eq!(SystemTime::now(), paused);
+
+                MockClock::advance_system_time(Duration::from_secs(1));
+                assert_eq!(paused.elapsed().unwrap(), Duration::from_secs(1));
+            }
+
+            #[test]
+            fn step_system_time_backward() {
+                let _lock = crate::test_lock();
+                MockClock::set_system_time(Duration::from_secs(10));
+
+                let before = SystemTime::now();
+                MockClock::step_system_time_backward(Duration::from_secs(3));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(7));
+
+                let err = before.elapsed().unwrap_err();
+                assert_eq!(err.duration(), Duration::from_secs(3));
+
+                MockClock::step_system_time_backward(Duration::from_secs(30));
+                assert_eq!(SystemTime::now(), UNIX_EPOCH);
+            }
+
             #[test]
             fn system_time_from_std_roundtrip() {
+                let _lock = crate::test_lock();
                 let std_now = std::time::SystemTime::now();
                 let mock_now: SystemTime = std_now.into();
                 assert!(mock_now.0 > Duration::from_secs(1708041600)); // Friday 16 February 2024 00:00:00 GMT
@@ -352,8 +1584,27 @@ macro_rules! define_instant_tests {
                 assert_eq!(std_now, roundtrip_now)
             }
 
+            #[test]
+            fn system_time_from_std_arithmetic_keeps_nanos() {
+                let _lock = crate::test_lock();
+
+                let std_time = std::time::UNIX_EPOCH + Duration::new(1_708_041_600, 123_456_789);
+                let mut mock_time = SystemTime::from(std_time) + Duration::from_nanos(7);
+                let roundtrip: std::time::SystemTime = mock_time.into();
+                assert_eq!(roundtrip, std_time + Duration::from_nanos(7));
+
+                mock_time -= Duration::from_nanos(7);
+                mock_time += Duration::from_nanos(3);
+                let roundtrip: std::time::SystemTime = mock_time.into();
+                assert_eq!(
+                    roundtrip.duration_since(std_time).unwrap(),
+                    Duration::from_nanos(3)
+                );
+            }
+
             #[test]
             fn set_time() {
+                let _lock = crate::test_lock();
                 reset_time();
 
                 MockClock::set_time(Duration::from_secs(42));
@@ -365,6 +1616,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn advance() {
+                let _lock = crate::test_lock();
                 reset_time();
 
                 for i in 0..3 {
@@ -376,6 +1628,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn instant() {
+                let _lock = crate::test_lock();
                 reset_time();
 
                 let now = Instant::now();
@@ -391,6 +1644,7 @@ macro_rules! define_instant_tests {
 
             #[test]
             fn methods() {
+                let _lock = crate::test_lock();
                 reset_time();
 
                 let instant = Instant::now();
@@ -466,10 +1720,1566 @@ macro_rules! define_instant_tests {
                     .checked_sub(Duration::from_millis(43))
                     .is_none());
             }
+
+            #[test]
+            fn last_op() {
+                let _lock = crate::test_lock();
+
+                MockClock::set_time(Duration::from_secs(3));
+                assert_eq!(
+                    MockClock::last_op(),
+                    Some($crate::ClockOp::Set(
+                        $crate::ClockKind::Instant,
+                        Duration::from_secs(3)
+                    ))
+                );
+
+                MockClock::advance(Duration::from_secs(2));
+                assert_eq!(
+                    MockClock::last_op(),
+                    Some($crate::ClockOp::Advance(
+                        $crate::ClockKind::Instant,
+                        Duration::from_secs(2)
+                    ))
+                );
+
+                MockClock::advance_system_time(Duration::from_millis(5));
+                assert_eq!(
+                    MockClock::last_op(),
+                    Some($crate::ClockOp::Advance(
+                        $crate::ClockKind::SystemTime,
+                        Duration::from_millis(5)
+                    ))
+                );
+
+                MockClock::set_system_time(Duration::ZERO);
+                assert_eq!(
+                    MockClock::last_op(),
+                    Some($crate::ClockOp::Set(
+                        $crate::ClockKind::SystemTime,
+                        Duration::ZERO
+                    ))
+                );
+            }
+
+            #[test]
+            fn aligns_with_system_time() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                MockClock::advance(Duration::from_secs(5));
+                MockClock::advance_system_time(Duration::from_secs(3));
+
+                let now = Instant::now();
+                assert!(!now.aligns_with(SystemTime::now()));
+                assert_eq!(now.offset_from(SystemTime::now()), 2_000_000_000);
+
+                MockClock::set_system_time(Duration::from_secs(8));
+                assert_eq!(now.offset_from(SystemTime::now()), -3_000_000_000);
+
+                MockClock::set_system_time(MockClock::time());
+                assert!(now.aligns_with(SystemTime::now()));
+                assert_eq!(now.offset_from(SystemTime::now()), 0);
+            }
+
+            #[test]
+            fn resume_from_suspend() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                MockClock::resume_from_suspend(Duration::from_secs(60));
+                assert_eq!(MockClock::time(), Duration::from_secs(60));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(60));
+
+                MockClock::resume_from_suspend_with_lag(
+                    Duration::from_secs(60),
+                    Duration::from_secs(15),
+                );
+                assert_eq!(MockClock::time(), Duration::from_secs(105));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(120));
+
+                MockClock::resume_from_suspend_with_lag(
+                    Duration::from_secs(1),
+                    Duration::from_secs(2),
+                );
+                assert_eq!(MockClock::time(), Duration::from_secs(105));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(121));
+            }
+
+            #[test]
+            fn elapsed_hms() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                let instant = Instant::now();
+                let system_time = SystemTime::now();
+                assert_eq!(instant.elapsed_hms(), (0, 0, 0, 0));
+                assert_eq!(system_time.elapsed_hms().unwrap(), (0, 0, 0, 0));
+
+                MockClock::advance(Duration::from_millis(59_999));
+                MockClock::advance_system_time(Duration::from_millis(59_999));
+                assert_eq!(instant.elapsed_hms(), (0, 0, 59, 999));
+                assert_eq!(system_time.elapsed_hms().unwrap(), (0, 0, 59, 999));
+
+                MockClock::advance(Duration::from_millis(1));
+                MockClock::advance_system_time(Duration::from_millis(1));
+                assert_eq!(instant.elapsed_hms(), (0, 1, 0, 0));
+                assert_eq!(system_time.elapsed_hms().unwrap(), (0, 1, 0, 0));
+
+                MockClock::advance(Duration::from_secs(2 * 3600 + 2 * 60 + 3));
+                assert_eq!(instant.elapsed_hms(), (2, 3, 3, 0));
+
+                MockClock::set_system_time(Duration::ZERO);
+                assert!(SystemTime(Duration::from_secs(1)).elapsed_hms().is_err());
+            }
+
+            #[test]
+            fn checked_mul_offset() {
+                let _lock = crate::test_lock();
+
+                let instant = Instant(Duration::from_millis(1500));
+                assert_eq!(
+                    instant.checked_mul_offset(2),
+                    Some(Instant(Duration::from_secs(3)))
+                );
+                assert_eq!(instant.checked_mul_offset(0), Some(Instant(Duration::ZERO)));
+                assert!(Instant(Duration::MAX).checked_mul_offset(2).is_none());
+
+                let system_time = SystemTime(Duration::from_millis(1500));
+                assert_eq!(
+                    system_time.checked_mul_offset(2),
+                    Some(SystemTime(Duration::from_secs(3)))
+                );
+                assert!(SystemTime(Duration::MAX).checked_mul_offset(2).is_none());
+            }
+
+            #[test]
+            fn on_advance_cancellable() {
+                let _lock = crate::test_lock();
+
+                let (tx, rx) = std::sync::mpsc::channel();
+                let mut remaining = 2;
+                MockClock::on_advance_cancellable(move |d| {
+                    tx.send(d).unwrap();
+                    remaining -= 1;
+                    if remaining == 0 {
+                        std::ops::ControlFlow::Break(())
+                    } else {
+                        std::ops::ControlFlow::Continue(())
+                    }
+                });
+
+                for i in 1..=4 {
+                    MockClock::advance(Duration::from_secs(i));
+                }
+
+                let seen: Vec<_> = rx.try_iter().collect();
+                assert_eq!(seen, [Duration::from_secs(1), Duration::from_secs(2)]);
+            }
+
+            #[test]
+            fn deadline() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(10));
+                MockClock::set_system_time(Duration::from_secs(20));
+
+                let after = Duration::from_millis(1500);
+                assert_eq!(MockClock::deadline(after), Instant::now() + after);
+                assert_eq!(MockClock::system_deadline(after), SystemTime::now() + after);
+
+                assert_eq!(MockClock::deadline(Duration::MAX), Instant(Duration::MAX));
+                assert_eq!(
+                    MockClock::system_deadline(Duration::MAX),
+                    SystemTime(Duration::MAX)
+                );
+            }
+
+            #[test]
+            fn assert_within() {
+                let _lock = crate::test_lock();
+
+                let a = Instant(Duration::from_millis(100));
+                let b = Instant(Duration::from_millis(150));
+                a.assert_within(b, Duration::from_millis(50));
+                b.assert_within(a, Duration::from_millis(50));
+                a.assert_within(a, Duration::ZERO);
+            }
+
+            #[test]
+            #[should_panic(expected = "difference: 50ms")]
+            fn assert_within_outside() {
+                let _lock = crate::test_lock();
+
+                let a = Instant(Duration::from_millis(100));
+                let b = Instant(Duration::from_millis(150));
+                a.assert_within(b, Duration::from_millis(49));
+            }
+
+            #[test]
+            fn advance_then_now() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                let instant = MockClock::advance_then_now(Duration::from_secs(3));
+                assert_eq!(instant, Instant(Duration::from_secs(3)));
+                assert_eq!(instant, Instant::now());
+
+                let system_time = MockClock::advance_system_then_now(Duration::from_secs(4));
+                assert_eq!(system_time, SystemTime(Duration::from_secs(4)));
+                assert_eq!(system_time, SystemTime::now());
+            }
+
+            #[test]
+            fn weighted_mean() {
+                let _lock = crate::test_lock();
+
+                let items = [
+                    (Instant(Duration::from_secs(10)), 1.0),
+                    (Instant(Duration::from_secs(20)), 3.0),
+                ];
+                assert_eq!(
+                    Instant::weighted_mean(&items),
+                    Some(Instant(Duration::from_millis(17_500)))
+                );
+
+                let items = [(Instant(Duration::from_secs(4)), 2.0)];
+                assert_eq!(
+                    Instant::weighted_mean(&items),
+                    Some(Instant(Duration::from_secs(4)))
+                );
+
+                assert_eq!(Instant::weighted_mean(&[]), None);
+                let items = [
+                    (Instant(Duration::from_secs(1)), 1.0),
+                    (Instant(Duration::from_secs(2)), -1.0),
+                ];
+                assert_eq!(Instant::weighted_mean(&items), None);
+            }
+
+            #[test]
+            fn resolution() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                MockClock::set_resolution(Duration::from_millis(15));
+                MockClock::advance(Duration::from_millis(20));
+                MockClock::advance_system_time(Duration::from_millis(20));
+                assert_eq!(MockClock::time(), Duration::from_millis(15));
+                assert_eq!(Instant::now(), Instant(Duration::from_millis(15)));
+                assert_eq!(MockClock::system_time(), Duration::from_millis(15));
+                assert_eq!(SystemTime::now(), SystemTime(Duration::from_millis(15)));
+
+                MockClock::advance(Duration::from_millis(10));
+                assert_eq!(MockClock::time(), Duration::from_millis(30));
+
+                MockClock::set_resolution(Duration::ZERO);
+                assert_eq!(MockClock::time(), Duration::from_millis(30));
+                assert_eq!(MockClock::system_time(), Duration::from_millis(20));
+            }
+
+            #[test]
+            fn assert_advanced() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                MockClock::advance(Duration::from_nanos(1));
+                MockClock::assert_advanced();
+            }
+
+            #[test]
+            #[should_panic(expected = "MockClock has not been advanced")]
+            fn assert_advanced_at_zero() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                MockClock::assert_advanced();
+            }
+
+            #[test]
+            fn set_time_to_process_uptime() {
+                let _lock = crate::test_lock();
+
+                MockClock::set_time_to_process_uptime();
+                let first = MockClock::time();
+
+                std::thread::sleep(Duration::from_millis(20));
+                MockClock::set_time_to_process_uptime();
+                let second = MockClock::time();
+
+                assert!(!second.is_zero());
+                assert!(second >= first + Duration::from_millis(20));
+                assert!(second < first + Duration::from_secs(10));
+            }
+
+            #[test]
+            fn process_uptime_starts_at_first_use() {
+                let _lock = crate::test_lock();
+
+                // the baseline is already there before the first call
+                MockClock::time();
+                std::thread::sleep(Duration::from_millis(20));
+                MockClock::set_time_to_process_uptime();
+
+                assert!(MockClock::time() >= Duration::from_millis(20));
+            }
+
+            #[test]
+            fn realtime_scaled() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                MockClock::set_realtime_scaled(10.0);
+                let start = std::time::Instant::now();
+                std::thread::sleep(Duration::from_millis(20));
+                let time = MockClock::time();
+                let real = start.elapsed();
+
+                MockClock::stop_realtime();
+                assert!(time >= Duration::from_millis(200), "{time:?}");
+                assert!(
+                    time <= (real * 10) + Duration::from_millis(10),
+                    "{time:?} vs {real:?}"
+                );
+
+                let frozen = MockClock::time();
+                std::thread::sleep(Duration::from_millis(5));
+                assert_eq!(MockClock::time(), frozen);
+                assert!(frozen >= time);
+            }
+
+            #[test]
+            fn nanosecond_precision() {
+                let _lock = crate::test_lock();
+
+                let earlier = Instant(Duration::new(1, 999_999_999));
+                let later = Instant(Duration::new(2, 0));
+                assert_eq!(
+                    later.checked_duration_since(earlier),
+                    Some(Duration::from_nanos(1))
+                );
+                assert_eq!(earlier.checked_duration_since(later), None);
+
+                let nano = Duration::from_nanos(1);
+                assert_eq!(earlier.checked_add(nano), Some(later));
+                assert_eq!(later.checked_sub(nano), Some(earlier));
+
+                let earlier = SystemTime(Duration::new(1, 999_999_999));
+                let later = SystemTime(Duration::new(2, 0));
+                assert_eq!(earlier.checked_add(nano), Some(later));
+                assert_eq!(later.checked_sub(nano), Some(earlier));
+                assert_eq!(later.duration_since(earlier).unwrap(), nano);
+            }
+
+            #[test]
+            fn steps_until() {
+                let start = Instant(Duration::from_secs(10));
+                let deadline = Instant(Duration::from_secs(20));
+
+                assert_eq!(start.steps_until(deadline, Duration::from_secs(1)), 10);
+                assert_eq!(start.steps_until(deadline, Duration::from_secs(3)), 3);
+                assert_eq!(start.steps_until(deadline, Duration::from_secs(10)), 1);
+                assert_eq!(start.steps_until(deadline, Duration::from_secs(11)), 0);
+                assert_eq!(start.steps_until(deadline, Duration::ZERO), 0);
+
+                assert_eq!(deadline.steps_until(start, Duration::from_secs(1)), 0);
+                assert_eq!(start.steps_until(start, Duration::from_secs(1)), 0);
+            }
+
+            #[test]
+            fn assert_clocks_synced() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(5));
+                MockClock::set_system_time(Duration::from_secs(5));
+                MockClock::assert_clocks_synced(Duration::ZERO);
+
+                MockClock::advance(Duration::from_millis(10));
+                MockClock::assert_clocks_synced(Duration::from_millis(10));
+            }
+
+            #[test]
+            #[should_panic(expected = "difference: -2s")]
+            fn assert_clocks_synced_differ() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(3));
+                MockClock::set_system_time(Duration::from_secs(5));
+                MockClock::assert_clocks_synced(Duration::from_secs(1));
+            }
+
+            #[test]
+            fn assert_both_elapsed() {
+                let _lock = crate::test_lock();
+                let (instant, system) = (Instant::now(), SystemTime::now());
+                MockClock::advance(Duration::from_secs(2));
+                MockClock::advance_system_time(Duration::from_secs(2));
+                MockClock::assert_both_elapsed(instant, system, Duration::from_secs(2));
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "but 2s has elapsed since Instant(1s) and Ok(1s) since SystemTime(1s)"
+            )]
+            fn assert_both_elapsed_differ() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+                MockClock::set_system_time(Duration::from_secs(1));
+                let (instant, system) = (Instant::now(), SystemTime::now());
+                MockClock::advance(Duration::from_secs(2));
+                MockClock::advance_system_time(Duration::from_secs(1));
+                MockClock::assert_both_elapsed(instant, system, Duration::from_secs(2));
+            }
+
+            #[test]
+            fn snapshot_diff() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(10));
+                MockClock::set_system_time(Duration::from_secs(20));
+
+                let before = MockClock::snapshot();
+                assert_eq!(before.time(), Duration::from_secs(10));
+                assert_eq!(before.system_time(), Duration::from_secs(20));
+
+                MockClock::advance(Duration::from_secs(5));
+                MockClock::advance_system_time(Duration::from_secs(5));
+                let after = MockClock::snapshot();
+                assert_eq!(before.diff(&after), (5_000_000_000, 5_000_000_000));
+
+                MockClock::set_system_time(Duration::from_secs(19));
+                let after = MockClock::snapshot();
+                assert_eq!(before.diff(&after), (5_000_000_000, -1_000_000_000));
+                assert_eq!(after.diff(&before), (-5_000_000_000, 1_000_000_000));
+            }
+
+            #[test]
+            fn timestamped() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let items: Vec<_> = ["a", "b", "c"]
+                    .into_iter()
+                    .inspect(|_| MockClock::advance(Duration::from_millis(10)))
+                    .timestamped()
+                    .collect();
+
+                assert_eq!(
+                    items,
+                    [
+                        (Instant(Duration::from_millis(10)), "a"),
+                        (Instant(Duration::from_millis(20)), "b"),
+                        (Instant(Duration::from_millis(30)), "c"),
+                    ]
+                );
+                assert!(items.windows(2).all(|w| w[0].0 < w[1].0));
+            }
+
+            #[test]
+            fn reset_single_clock() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(3));
+                MockClock::set_system_time(Duration::from_secs(4));
+
+                MockClock::reset_system_time();
+                assert_eq!(MockClock::system_time(), Duration::ZERO);
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+
+                MockClock::set_system_time(Duration::from_secs(4));
+                MockClock::reset_time();
+                assert_eq!(MockClock::time(), Duration::ZERO);
+                assert_eq!(MockClock::system_time(), Duration::from_secs(4));
+            }
+
+            #[test]
+            fn init_from_env() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+                MockClock::set_system_time(Duration::from_secs(1));
+
+                std::env::remove_var($crate::START_MS_VAR);
+                assert_eq!(MockClock::init_from_env(), Ok(None));
+                assert_eq!(MockClock::time(), Duration::from_secs(1));
+
+                std::env::set_var($crate::START_MS_VAR, "not a number");
+                assert!(MockClock::init_from_env().is_err());
+                assert_eq!(MockClock::time(), Duration::from_secs(1));
+
+                std::env::set_var($crate::START_MS_VAR, "1500");
+                assert_eq!(
+                    MockClock::init_from_env(),
+                    Ok(Some(Duration::from_millis(1500)))
+                );
+                assert_eq!(MockClock::time(), Duration::from_millis(1500));
+                assert_eq!(MockClock::system_time(), Duration::from_millis(1500));
+
+                std::env::remove_var($crate::START_MS_VAR);
+            }
+
+            #[test]
+            fn with_speed() {
+                let _lock = crate::test_lock();
+                assert_eq!(MockClock::realtime_scale(), None);
+
+                let inside = MockClock::with_speed(4.0, MockClock::realtime_scale);
+                assert_eq!(inside, Some(4.0));
+                assert_eq!(MockClock::realtime_scale(), None);
+
+                MockClock::set_realtime_scaled(2.0);
+                MockClock::with_speed(8.0, || {
+                    assert_eq!(MockClock::realtime_scale(), Some(8.0));
+                });
+                assert_eq!(MockClock::realtime_scale(), Some(2.0));
+
+                let result = std::panic::catch_unwind(|| {
+                    MockClock::with_speed(16.0, || panic!("inside with_speed"))
+                });
+                assert!(result.is_err());
+                assert_eq!(MockClock::realtime_scale(), Some(2.0));
+
+                MockClock::stop_realtime();
+            }
+
+            #[test]
+            fn unit_since() {
+                let _lock = crate::test_lock();
+
+                let earlier = Instant(Duration::from_secs(1));
+                let later = Instant(Duration::new(3, 500_250_125));
+                assert_eq!(later.secs_since(earlier), 2);
+                assert_eq!(later.millis_since(earlier), 2_500);
+                assert_eq!(later.micros_since(earlier), 2_500_250);
+                assert_eq!(later.nanos_since(earlier), 2_500_250_125);
+                assert_eq!(earlier.secs_since(later), 0);
+                assert_eq!(earlier.nanos_since(later), 0);
+
+                let earlier = SystemTime(Duration::from_secs(1));
+                let later = SystemTime(Duration::new(3, 500_250_125));
+                assert_eq!(later.secs_since(earlier).unwrap(), 2);
+                assert_eq!(later.millis_since(earlier).unwrap(), 2_500);
+                assert_eq!(later.micros_since(earlier).unwrap(), 2_500_250);
+                assert_eq!(later.nanos_since(earlier).unwrap(), 2_500_250_125);
+                assert!(earlier.secs_since(later).is_err());
+            }
+
+            #[test]
+            fn compare_with_duration() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(3));
+
+                let now = Instant::now();
+                assert!(now == Duration::from_secs(3));
+                assert!(now < Duration::from_secs(5));
+                assert!(now > Duration::from_secs(2));
+                assert!(now >= Duration::from_secs(3));
+
+                MockClock::advance(Duration::from_secs(3));
+                assert!(Instant::now() > Duration::from_secs(5));
+                assert!(now.elapsed() == Duration::from_secs(3));
+            }
+
+            #[test]
+            fn now_call_count() {
+                let _lock = crate::test_lock();
+
+                MockClock::reset_now_call_count();
+                assert_eq!(MockClock::now_call_count(), 0);
+
+                let _ = Instant::now();
+                let _ = Instant::now();
+                let _ = SystemTime::now();
+                let _ = MockClock::time();
+                assert_eq!(MockClock::now_call_count(), 3);
+
+                MockClock::reset_now_call_count();
+                assert_eq!(MockClock::now_call_count(), 0);
+            }
+
+            #[test]
+            fn advance_to() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(10));
+
+                MockClock::advance_to(Instant(Duration::from_secs(15)));
+                assert_eq!(MockClock::time(), Duration::from_secs(15));
+                assert_eq!(
+                    MockClock::last_op(),
+                    Some($crate::ClockOp::Advance(
+                        $crate::ClockKind::Instant,
+                        Duration::from_secs(5)
+                    ))
+                );
+
+                MockClock::advance_to(Instant(Duration::from_secs(12)));
+                assert_eq!(MockClock::time(), Duration::from_secs(15));
+
+                MockClock::advance_to(Instant(Duration::from_secs(15)));
+                assert_eq!(MockClock::time(), Duration::from_secs(15));
+            }
+
+            #[test]
+            fn boot_time() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(10));
+                MockClock::set_boot_time(Duration::from_secs(10));
+
+                let instant = Instant::now();
+                let boot = BootInstant::now();
+
+                // a suspend counts towards boot time, but not monotonic time
+                MockClock::advance_boot_time(Duration::from_secs(60));
+                assert_eq!(boot.elapsed(), Duration::from_secs(60));
+                assert_eq!(instant.elapsed(), Duration::ZERO);
+
+                MockClock::advance(Duration::from_secs(1));
+                assert_eq!(boot.elapsed(), Duration::from_secs(60));
+                assert_eq!(instant.elapsed(), Duration::from_secs(1));
+
+                assert_eq!(MockClock::boot_time(), Duration::from_secs(70));
+                assert_eq!(BootInstant::now() - boot, Duration::from_secs(60));
+                assert_eq!(boot.is_thread_local(), MockClock::is_thread_local());
+            }
+
+            #[test]
+            fn quantized() {
+                let resolution = Duration::from_millis(10);
+                assert_eq!(
+                    Instant(Duration::from_millis(27)).quantized(resolution),
+                    Instant(Duration::from_millis(20))
+                );
+                assert_eq!(
+                    Instant(Duration::from_millis(30)).quantized(resolution),
+                    Instant(Duration::from_millis(30))
+                );
+                assert_eq!(
+                    Instant(Duration::from_micros(9_999)).quantized(resolution),
+                    Instant(Duration::ZERO)
+                );
+                assert_eq!(
+                    Instant(Duration::from_nanos(27)).quantized(Duration::ZERO),
+                    Instant(Duration::from_nanos(27))
+                );
+            }
+
+            #[test]
+            fn is_frozen() {
+                let _lock = crate::test_lock();
+                assert!(MockClock::is_frozen());
+
+                MockClock::set_realtime_scaled(1.0);
+                assert!(!MockClock::is_frozen());
+
+                MockClock::stop_realtime();
+                assert!(MockClock::is_frozen());
+            }
+
+            #[test]
+            fn from_tuple() {
+                let cases = [
+                    ((0, 0), Duration::ZERO),
+                    ((1, 500), Duration::new(1, 500)),
+                    ((2, 1_500_000_000), Duration::from_millis(3_500)),
+                ];
+                for (tuple, expected) in cases {
+                    assert_eq!(Instant::from(tuple), Instant(expected));
+                    assert_eq!(SystemTime::from(tuple), SystemTime(expected));
+                }
+            }
+
+            #[test]
+            fn assert_elapsed() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                let instant = Instant::now();
+                let system_time = SystemTime::now();
+                MockClock::advance(Duration::from_secs(2));
+                MockClock::advance_system_time(Duration::from_secs(3));
+
+                instant.assert_elapsed(Duration::from_secs(2));
+                system_time.assert_elapsed(Duration::from_secs(3));
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "expected 1s to have elapsed since Instant(0ns), but 2s has elapsed"
+            )]
+            fn assert_elapsed_mismatch() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let instant = Instant::now();
+                MockClock::advance(Duration::from_secs(2));
+                instant.assert_elapsed(Duration::from_secs(1));
+            }
+
+            #[test]
+            #[should_panic(expected = "but 2s has elapsed")]
+            fn assert_elapsed_system_time_mismatch() {
+                let _lock = crate::test_lock();
+                reset_system_time();
+
+                let system_time = SystemTime::now();
+                MockClock::advance_system_time(Duration::from_secs(2));
+                system_time.assert_elapsed(Duration::from_secs(1));
+            }
+
+            #[test]
+            #[should_panic(expected = "but the clock is 1s earlier")]
+            fn assert_elapsed_system_time_earlier() {
+                let _lock = crate::test_lock();
+                MockClock::set_system_time(Duration::from_secs(1));
+
+                SystemTime(Duration::from_secs(2)).assert_elapsed(Duration::ZERO);
+            }
+
+            #[test]
+            fn ordered_instant() {
+                let _lock = crate::test_lock();
+
+                let early = Instant(Duration::from_secs(1));
+                let late = Instant(Duration::from_secs(2));
+
+                let first = OrderedInstant::new(late);
+                let second = OrderedInstant::new(early);
+                let third = OrderedInstant::new(late);
+                let set: std::collections::BTreeSet<_> =
+                    [third, first, second].into_iter().collect();
+
+                assert_eq!(set.len(), 3);
+                assert_eq!(set.into_iter().collect::<Vec<_>>(), [second, first, third]);
+                assert_eq!(first.instant(), third.instant());
+                assert!(first.sequence() < third.sequence());
+            }
+
+            #[test]
+            fn run_steps() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let mut seen = vec![];
+                let out = MockClock::run_steps(Duration::from_secs(2), |now| {
+                    seen.push(now);
+                    if seen.len() == 3 {
+                        std::ops::ControlFlow::Break("done")
+                    } else {
+                        std::ops::ControlFlow::Continue(())
+                    }
+                });
+
+                assert_eq!(out, Some("done"));
+                assert_eq!(
+                    seen,
+                    [
+                        Instant(Duration::from_secs(2)),
+                        Instant(Duration::from_secs(4)),
+                        Instant(Duration::from_secs(6)),
+                    ]
+                );
+                assert_eq!(MockClock::time(), Duration::from_secs(6));
+            }
+
+            #[test]
+            fn run_steps_limit() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let out = MockClock::run_steps(Duration::from_nanos(1), |_| {
+                    std::ops::ControlFlow::<()>::Continue(())
+                });
+                assert_eq!(out, None);
+                assert_eq!(
+                    MockClock::time(),
+                    Duration::from_nanos(MockClock::RUN_STEPS_LIMIT as u64)
+                );
+            }
+
+            #[test]
+            fn into_inner() {
+                let _lock = crate::test_lock();
+
+                let d = Duration::new(3, 141_592_653);
+                assert_eq!(Instant::from(d).into_inner(), d);
+                assert_eq!(SystemTime::from(d).into_inner(), d);
+
+                MockClock::set_time(d);
+                MockClock::set_system_time(d);
+                assert_eq!(Instant::now().into_inner(), d);
+                assert_eq!(SystemTime::now().into_inner(), d);
+            }
+
+            #[test]
+            fn with_frozen_now() {
+                let _lock = crate::test_lock();
+                MockClock::set_system_time(Duration::from_secs(1));
+
+                MockClock::set_realtime_scaled(1_000.0);
+                let (first, second, system_time) = MockClock::with_frozen_now(|| {
+                    let first = Instant::now();
+                    std::thread::sleep(Duration::from_millis(2));
+                    MockClock::advance(Duration::from_secs(1));
+                    MockClock::advance_system_time(Duration::from_secs(1));
+                    assert_eq!(SystemTime::now(), SystemTime(Duration::from_secs(1)));
+                    (first, Instant::now(), MockClock::system_time())
+                });
+                MockClock::stop_realtime();
+
+                assert_eq!(first, second);
+                assert_eq!(system_time, Duration::from_secs(2));
+                assert!(Instant::now() > first + Duration::from_secs(1));
+                assert_eq!(SystemTime::now(), SystemTime(Duration::from_secs(2)));
+            }
+
+            #[test]
+            fn advance_to_next_period() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_millis(2_500));
+
+                MockClock::advance_to_next_period(Duration::from_secs(1));
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+
+                MockClock::advance_to_next_period(Duration::from_secs(1));
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+
+                MockClock::advance_to_next_period(Duration::from_secs(5));
+                assert_eq!(MockClock::time(), Duration::from_secs(5));
+
+                MockClock::advance_to_next_period(Duration::ZERO);
+                assert_eq!(MockClock::time(), Duration::from_secs(5));
+            }
+
+            #[test]
+            fn elapsed_capped() {
+                let _lock = crate::test_lock();
+                reset_time();
+                MockClock::set_system_time(Duration::from_secs(10));
+
+                let instant = Instant::now();
+                let system_time = SystemTime::now();
+                let max = Duration::from_secs(5);
+
+                MockClock::advance(Duration::from_secs(3));
+                MockClock::advance_system_time(Duration::from_secs(3));
+                assert_eq!(instant.elapsed_capped(max), Duration::from_secs(3));
+                assert_eq!(
+                    system_time.elapsed_capped(max).unwrap(),
+                    Duration::from_secs(3)
+                );
+
+                MockClock::advance(Duration::from_secs(3));
+                MockClock::advance_system_time(Duration::from_secs(3));
+                assert_eq!(instant.elapsed_capped(max), max);
+                assert_eq!(system_time.elapsed_capped(max).unwrap(), max);
+
+                MockClock::set_system_time(Duration::ZERO);
+                assert!(system_time.elapsed_capped(max).is_err());
+            }
+
+            #[test]
+            fn time_token() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(10));
+
+                let mut tokens = vec![MockClock::time_token(), MockClock::time_token()];
+                MockClock::advance(Duration::from_nanos(1));
+                tokens.push(MockClock::time_token());
+                MockClock::set_time(Duration::from_secs(1));
+                tokens.push(MockClock::time_token());
+                MockClock::set_time(Duration::from_secs(20));
+                tokens.push(MockClock::time_token());
+
+                assert!(tokens.windows(2).all(|w| w[0] < w[1]), "{tokens:#?}");
+                assert!(tokens.iter().all(|token| token.len() == 32 + 1 + 16));
+                assert!(tokens[4].starts_with(&format!("{:032x}-", 20_000_000_000_u128)));
+            }
+
+            #[test]
+            fn boxed_clock() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+                MockClock::set_system_time(Duration::from_secs(2));
+
+                struct Service {
+                    clock: Box<dyn Clock + Send + Sync>,
+                }
+
+                let service = Service {
+                    clock: MockClock::boxed(),
+                };
+                let start = service.clock.now();
+                let system_start = service.clock.system_now();
+                assert_eq!(start, Instant(Duration::from_secs(1)));
+                assert_eq!(system_start, SystemTime(Duration::from_secs(2)));
+
+                MockClock::advance(Duration::from_secs(3));
+                MockClock::advance_system_time(Duration::from_secs(3));
+                assert_eq!(service.clock.now() - start, Duration::from_secs(3));
+                assert_eq!(
+                    service
+                        .clock
+                        .system_now()
+                        .duration_since(system_start)
+                        .unwrap(),
+                    Duration::from_secs(3)
+                );
+            }
+
+            #[test]
+            fn range() {
+                let range = |start, end| Range {
+                    start: Instant(Duration::from_secs(start)),
+                    end: Instant(Duration::from_secs(end)),
+                };
+
+                // disjoint
+                assert!(!range(0, 5).overlaps(&range(6, 10)));
+                assert_eq!(range(0, 5).intersection(&range(6, 10)), None);
+
+                // touching
+                assert!(!range(0, 5).overlaps(&range(5, 10)));
+                assert!(!range(5, 10).overlaps(&range(0, 5)));
+                assert_eq!(range(0, 5).intersection(&range(5, 10)), None);
+
+                // overlapping
+                assert!(range(0, 6).overlaps(&range(5, 10)));
+                assert!(range(5, 10).overlaps(&range(0, 6)));
+                assert_eq!(range(0, 6).intersection(&range(5, 10)), Some(range(5, 6)));
+
+                // contained
+                assert!(range(0, 10).overlaps(&range(2, 3)));
+                assert_eq!(range(0, 10).intersection(&range(2, 3)), Some(range(2, 3)));
+            }
+
+            #[test]
+            fn budget() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let budget = Budget::new(Duration::from_secs(10));
+                assert_eq!(budget.remaining(), Duration::from_secs(10));
+                assert!(!budget.is_exhausted());
+
+                MockClock::advance(Duration::from_secs(4));
+                assert_eq!(budget.remaining(), Duration::from_secs(6));
+                assert!(!budget.is_exhausted());
+
+                MockClock::advance(Duration::from_secs(6));
+                assert_eq!(budget.remaining(), Duration::ZERO);
+                assert!(budget.is_exhausted());
+
+                MockClock::advance(Duration::from_secs(6));
+                assert_eq!(budget.remaining(), Duration::ZERO);
+                assert!(budget.is_exhausted());
+
+                // moving the clock back before the start gives the whole budget back
+                let later = Budget::new(Duration::from_secs(10));
+                MockClock::set_time(Duration::ZERO);
+                assert_eq!(later.remaining(), Duration::from_secs(10));
+                assert!(!later.is_exhausted());
+            }
+
+            #[test]
+            fn progress_toward() {
+                let _lock = crate::test_lock();
+
+                let start = Instant(Duration::from_secs(10));
+                let total = Duration::from_secs(4);
+                let at = |secs| Instant(Duration::from_secs(secs)).progress_toward(start, total);
+
+                assert_eq!(at(5), 0.0);
+                assert_eq!(at(10), 0.0);
+                assert_eq!(at(12), 0.5);
+                assert_eq!(at(14), 1.0);
+                assert_eq!(at(100), 1.0);
+
+                assert_eq!(start.progress_toward(start, Duration::ZERO), 1.0);
+            }
+
+            #[test]
+            fn reset_returning() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(3));
+                MockClock::set_system_time(Duration::from_secs(5));
+
+                let (time, system_time) = MockClock::reset_returning();
+                assert_eq!(
+                    (time, system_time),
+                    (Duration::from_secs(3), Duration::from_secs(5))
+                );
+                assert_eq!(MockClock::time(), Duration::ZERO);
+                assert_eq!(MockClock::system_time(), Duration::ZERO);
+
+                MockClock::set_time(time);
+                MockClock::set_system_time(system_time);
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(5));
+            }
+
+            #[test]
+            fn stall_system_time() {
+                let _lock = crate::test_lock();
+                reset_time();
+                MockClock::set_system_time(Duration::from_secs(10));
+
+                MockClock::stall_system_time(3);
+                for _ in 0..3 {
+                    MockClock::advance(Duration::from_secs(1));
+                    MockClock::advance_system_time(Duration::from_secs(1));
+                    assert_eq!(SystemTime::now(), UNIX_EPOCH + Duration::from_secs(10));
+                }
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+                assert_eq!(SystemTime::now(), UNIX_EPOCH + Duration::from_secs(13));
+            }
+
+            #[test]
+            fn expect_advance() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let _guard = MockClock::expect_advance(Duration::from_secs(3));
+                MockClock::advance(Duration::from_secs(1));
+                MockClock::advance(Duration::from_secs(2));
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "expected the clock to advance by 3s, but it went from 0ns to 2s"
+            )]
+            fn expect_advance_mismatch() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let _guard = MockClock::expect_advance(Duration::from_secs(3));
+                MockClock::advance(Duration::from_secs(2));
+            }
+
+            #[test]
+            fn floor() {
+                let _lock = crate::test_lock();
+                MockClock::set_floor(Duration::from_secs(5));
+
+                let instant = Instant(Duration::from_secs(8));
+                assert_eq!(
+                    instant.checked_sub(Duration::from_secs(3)),
+                    Some(Instant(Duration::from_secs(5)))
+                );
+                assert_eq!(instant.checked_sub(Duration::from_secs(4)), None);
+                assert_eq!(instant.checked_sub(Duration::from_secs(40)), None);
+                assert!(std::panic::catch_unwind(|| instant - Duration::from_secs(4)).is_err());
+
+                MockClock::set_floor_mode(crate::FloorMode::Clamp);
+                assert_eq!(
+                    instant.checked_sub(Duration::from_secs(4)),
+                    Some(Instant(Duration::from_secs(5)))
+                );
+                assert_eq!(
+                    instant - Duration::from_secs(40),
+                    Instant(Duration::from_secs(5))
+                );
+                let mut clamped = instant;
+                clamped -= Duration::from_secs(40);
+                assert_eq!(clamped, Instant(Duration::from_secs(5)));
+
+                // already below the floor
+                let below = Instant(Duration::from_secs(2));
+                assert_eq!(below.checked_sub(Duration::from_secs(1)), Some(below));
+
+                MockClock::set_floor(Duration::ZERO);
+                MockClock::set_floor_mode(crate::FloorMode::Error);
+            }
+
+            #[test]
+            fn system_time_next_multiple_of() {
+                let _lock = crate::test_lock();
+                let hour = Duration::from_secs(60 * 60);
+
+                let aligned = UNIX_EPOCH + hour * 3;
+                assert_eq!(aligned.next_multiple_of(hour), Some(aligned));
+
+                let mid = UNIX_EPOCH + hour * 3 + Duration::from_secs(1);
+                assert_eq!(mid.next_multiple_of(hour), Some(UNIX_EPOCH + hour * 4));
+
+                assert_eq!(mid.next_multiple_of(Duration::ZERO), Some(mid));
+
+                let max = SystemTime(Duration::MAX);
+                assert_eq!(max.next_multiple_of(hour), None);
+                assert_eq!(max.next_multiple_of(Duration::from_nanos(1)), Some(max));
+            }
+
+            #[test]
+            fn for_each_time() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(42));
+
+                let times = [1, 5, 10].map(Duration::from_secs);
+                let mut seen = vec![];
+                MockClock::for_each_time(&times, || seen.push(Instant::now()));
+
+                assert_eq!(seen, times.map(Instant));
+                assert_eq!(MockClock::time(), Duration::from_secs(42));
+            }
+
+            #[test]
+            fn snapshot_bytes() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::new(3, 5));
+                MockClock::set_system_time(Duration::new(1_700_000_000, 123_456_789));
+
+                let snapshot = MockClock::snapshot();
+                let bytes = snapshot.to_bytes();
+                assert_eq!(bytes[..8], 3_000_000_005u64.to_be_bytes());
+                assert_eq!(crate::ClockSnapshot::from_bytes(&bytes), snapshot);
+
+                let max = Duration::from_nanos(u64::MAX);
+                MockClock::set_time(max);
+                MockClock::set_system_time(max - Duration::from_nanos(1));
+                let snapshot = MockClock::snapshot();
+                assert_eq!(
+                    crate::ClockSnapshot::from_bytes(&snapshot.to_bytes()),
+                    snapshot
+                );
+
+                // past u64::MAX nanoseconds saturates
+                MockClock::set_time(max + Duration::from_nanos(1));
+                let snapshot = crate::ClockSnapshot::from_bytes(&MockClock::snapshot().to_bytes());
+                assert_eq!(snapshot.time(), max);
+
+                reset_time();
+                reset_system_time();
+            }
+
+            #[test]
+            fn advance_frames() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                MockClock::advance_frames(90);
+                assert_eq!(MockClock::time(), Duration::from_millis(1_500));
+                assert_eq!(Instant::now().frame_number(60.0), 90);
+
+                MockClock::advance_frames(1);
+                assert_eq!(Instant::now().frame_number(60.0), 91);
+
+                reset_time();
+                MockClock::set_frame_rate(30.0);
+                MockClock::advance_frames(90);
+                assert_eq!(MockClock::time(), Duration::from_secs(3));
+                assert_eq!(Instant::now().frame_number(30.0), 90);
+                assert_eq!(Instant::now().frame_number(60.0), 180);
+
+                MockClock::set_frame_rate(60.0);
+            }
+
+            #[test]
+            fn checkpoint() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let start = Instant::now();
+                assert_eq!(start.checkpoint(), Duration::ZERO);
+
+                // the task is suspended at an await while time passes
+                MockClock::advance(Duration::from_millis(250));
+                assert_eq!(start.checkpoint(), Duration::from_millis(250));
+
+                MockClock::advance(Duration::from_millis(750));
+                assert_eq!(start.checkpoint(), Duration::from_secs(1));
+
+                // the clock was moved back while the task was suspended
+                let later = Instant::now();
+                MockClock::set_time(Duration::ZERO);
+                assert_eq!(later.checkpoint(), Duration::ZERO);
+            }
+
+            #[test]
+            fn warp_to() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+                MockClock::set_system_time(Duration::from_secs(2));
+
+                let previous =
+                    MockClock::warp_to(Duration::from_secs(1_700_000_000), Duration::from_secs(60));
+                assert_eq!(previous, (Duration::from_secs(2), Duration::from_secs(1)));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(1_700_000_000));
+                assert_eq!(MockClock::time(), Duration::from_secs(60));
+
+                reset_time();
+                reset_system_time();
+            }
+
+            #[test]
+            fn backoff_since() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let start = Instant::now();
+                let base = Duration::from_millis(100);
+                let max = Duration::from_secs(1);
+                let backoff = || start.backoff_since(base, 2.0, max);
+
+                assert_eq!(backoff(), Duration::from_millis(100));
+
+                MockClock::advance(Duration::from_millis(150));
+                assert_eq!(backoff(), Duration::from_millis(200));
+
+                MockClock::advance(Duration::from_millis(150));
+                assert_eq!(backoff(), Duration::from_millis(800));
+
+                MockClock::advance(Duration::from_secs(10));
+                assert_eq!(backoff(), max);
+            }
+
+            #[test]
+            fn system_time_would_error_since() {
+                let _lock = crate::test_lock();
+
+                let earlier = UNIX_EPOCH + Duration::from_secs(1);
+                let later = UNIX_EPOCH + Duration::from_secs(2);
+                assert!(!later.would_error_since(earlier));
+                assert!(!later.would_error_since(later));
+                assert!(earlier.would_error_since(later));
+                assert_eq!(
+                    earlier.would_error_since(later),
+                    earlier.duration_since(later).is_err()
+                );
+            }
+
+            #[test]
+            fn advance_everything() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+                MockClock::set_system_time(Duration::from_secs(2));
+                MockClock::set_boot_time(Duration::from_secs(3));
+
+                MockClock::advance_everything(Duration::from_secs(10));
+                assert_eq!(MockClock::time(), Duration::from_secs(11));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(12));
+                assert_eq!(MockClock::boot_time(), Duration::from_secs(13));
+
+                reset_time();
+                reset_system_time();
+                MockClock::set_boot_time(Duration::ZERO);
+            }
+
+            #[test]
+            fn random_walk() {
+                let _lock = crate::test_lock();
+                let (min, max) = (Duration::from_millis(10), Duration::from_millis(20));
+
+                reset_time();
+                let walk = MockClock::random_walk(42, 100, min, max);
+                let end = MockClock::time();
+
+                assert_eq!(walk.len(), 100);
+                assert_eq!(walk.last(), Some(&end));
+                let mut previous = Duration::ZERO;
+                for &time in &walk {
+                    assert!((min..=max).contains(&(time - previous)));
+                    previous = time;
+                }
+
+                reset_time();
+                assert_eq!(MockClock::random_walk(42, 100, min, max), walk);
+                assert_eq!(MockClock::time(), end);
+
+                reset_time();
+                assert_ne!(MockClock::random_walk(43, 100, min, max), walk);
+
+                reset_time();
+                let fixed = MockClock::random_walk(7, 3, min, min);
+                assert_eq!(fixed, [min, min * 2, min * 3]);
+                reset_time();
+            }
+
+            #[test]
+            fn system_time_midpoint() {
+                let request = UNIX_EPOCH + Duration::from_millis(100);
+                let response = UNIX_EPOCH + Duration::from_millis(300);
+                assert_eq!(
+                    request.midpoint(response),
+                    UNIX_EPOCH + Duration::from_millis(200)
+                );
+                assert_eq!(
+                    response.midpoint(request),
+                    UNIX_EPOCH + Duration::from_millis(200)
+                );
+                assert_eq!(request.midpoint(request), request);
+
+                let odd = UNIX_EPOCH + Duration::from_nanos(3);
+                assert_eq!(
+                    UNIX_EPOCH.midpoint(odd),
+                    UNIX_EPOCH + Duration::from_nanos(1)
+                );
+
+                let max = SystemTime(Duration::MAX);
+                let near_max = SystemTime(Duration::MAX - Duration::from_secs(2));
+                assert_eq!(
+                    max.midpoint(near_max),
+                    SystemTime(Duration::MAX - Duration::from_secs(1))
+                );
+                assert_eq!(
+                    UNIX_EPOCH.midpoint(max),
+                    SystemTime(Duration::new(u64::MAX / 2, 999_999_999))
+                );
+            }
+
+            #[test]
+            fn advance_until_elapsed() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let start = Instant::now();
+                MockClock::advance(Duration::from_secs(2));
+                MockClock::advance_until_elapsed(start, Duration::from_secs(5));
+                assert_eq!(start.elapsed(), Duration::from_secs(5));
+
+                // already met
+                MockClock::advance_until_elapsed(start, Duration::from_secs(3));
+                assert_eq!(start.elapsed(), Duration::from_secs(5));
+            }
+
+            #[test]
+            fn catch_arithmetic() {
+                let _lock = crate::test_lock();
+
+                let instant = Instant(Duration::MAX);
+                let err =
+                    MockClock::catch_arithmetic(|| instant + Duration::from_secs(1)).unwrap_err();
+                assert_eq!(err.message(), "overflow when adding durations");
+
+                let err = MockClock::catch_arithmetic(|| UNIX_EPOCH - Duration::from_secs(1))
+                    .unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "overflow when subtracting duration from instant"
+                );
+
+                assert_eq!(
+                    MockClock::catch_arithmetic(|| instant - Duration::MAX),
+                    Ok(Instant(Duration::ZERO))
+                );
+
+                // other panics aren't caught
+                let other = std::panic::catch_unwind(|| {
+                    MockClock::catch_arithmetic(|| panic!("not arithmetic"))
+                });
+                assert!(other.is_err());
+            }
+
+            #[test]
+            fn resolution_in_effect() {
+                let _lock = crate::test_lock();
+                assert_eq!(MockClock::resolution(), Duration::from_nanos(1));
+
+                MockClock::set_resolution(Duration::from_millis(15));
+                assert_eq!(MockClock::resolution(), Duration::from_millis(15));
+
+                MockClock::set_resolution(Duration::ZERO);
+                assert_eq!(MockClock::resolution(), Duration::from_nanos(1));
+            }
+
+            #[test]
+            fn pause_system_time() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+
+                MockClock::pause_system_time();
+                MockClock::advance_everything(Duration::from_secs(3));
+                MockClock::resume_from_suspend(Duration::from_secs(4));
+                assert_eq!(MockClock::time(), Duration::from_secs(7));
+                assert_eq!(MockClock::system_time(), Duration::ZERO);
+
+                MockClock::resume_system_time();
+                MockClock::advance_everything(Duration::from_secs(1));
+                assert_eq!(MockClock::time(), Duration::from_secs(8));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(1));
+
+                reset_time();
+                reset_system_time();
+                MockClock::set_boot_time(Duration::ZERO);
+            }
+
+            #[test]
+            fn assert_elapsed_macro() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                let start = Instant::now();
+                MockClock::advance(Duration::from_secs(2));
+                crate::assert_elapsed!(start, Duration::from_secs(2));
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "expected 3s to have elapsed, but 2s has elapsed (the instant is at 1s, the clock is at 3s)"
+            )]
+            fn assert_elapsed_macro_message() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(1));
+
+                let start = Instant::now();
+                MockClock::advance(Duration::from_secs(2));
+                crate::assert_elapsed!(start, Duration::from_secs(3));
+            }
+
+            #[test]
+            #[should_panic(
+                expected = "expected 1s to have elapsed, but the instant is 2s in the future (the instant is at 5s, the clock is at 3s)"
+            )]
+            fn assert_elapsed_macro_future() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(5));
+
+                let start = Instant::now();
+                MockClock::set_time(Duration::from_secs(3));
+                crate::assert_elapsed!(start, Duration::from_secs(1));
+            }
+
+            #[test]
+            fn system_time_is_stale() {
+                let _lock = crate::test_lock();
+                MockClock::set_system_time(Duration::from_secs(100));
+                let ttl = Duration::from_secs(10);
+
+                let cached = SystemTime::now();
+                assert!(!cached.is_stale(ttl));
+
+                MockClock::advance_system_time(Duration::from_secs(9));
+                assert!(!cached.is_stale(ttl));
+
+                MockClock::advance_system_time(Duration::from_secs(1));
+                assert!(cached.is_stale(ttl));
+
+                MockClock::advance_system_time(Duration::from_secs(100));
+                assert!(cached.is_stale(ttl));
+
+                let future = SystemTime::now() + Duration::from_secs(1);
+                assert!(!future.is_stale(Duration::ZERO));
+
+                reset_system_time();
+            }
+
+            #[test]
+            fn uptime() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_secs(100));
+                assert_eq!(MockClock::uptime(), Duration::from_secs(100));
+
+                MockClock::set_process_start(Duration::from_secs(100));
+                assert_eq!(MockClock::uptime(), Duration::ZERO);
+
+                MockClock::advance(Duration::from_secs(5));
+                assert_eq!(MockClock::uptime(), Duration::from_secs(5));
+                assert_eq!(MockClock::time(), Duration::from_secs(105));
+
+                MockClock::set_time(Duration::from_secs(50));
+                assert_eq!(MockClock::uptime(), Duration::ZERO);
+
+                MockClock::set_process_start(Duration::ZERO);
+                reset_time();
+            }
+
+            #[test]
+            fn assert_system_not_behind() {
+                let _lock = crate::test_lock();
+                let (instant, system) = (Instant::now(), SystemTime::now());
+
+                MockClock::advance(Duration::from_secs(1));
+                MockClock::advance_system_time(Duration::from_secs(1));
+                MockClock::assert_system_not_behind(instant, system);
+
+                MockClock::advance_system_time(Duration::from_secs(1));
+                MockClock::assert_system_not_behind(instant, system);
+            }
+
+            #[test]
+            #[should_panic(expected = "the system clock is behind: it advanced 1s since SystemTime(0ns), but the monotonic clock advanced 2s")]
+            fn assert_system_behind() {
+                let _lock = crate::test_lock();
+                reset_time();
+                reset_system_time();
+                let (instant, system) = (Instant::now(), SystemTime::now());
+
+                MockClock::advance(Duration::from_secs(2));
+                MockClock::advance_system_time(Duration::from_secs(1));
+                MockClock::assert_system_not_behind(instant, system);
+            }
+
+            #[test]
+            fn independent_resolutions() {
+                let _lock = crate::test_lock();
+                MockClock::set_time(Duration::from_millis(2_345_600));
+                MockClock::set_system_time(Duration::from_millis(2_345_600));
+
+                MockClock::set_instant_resolution(Duration::from_millis(1));
+                MockClock::set_system_time_resolution(Duration::from_secs(1));
+                assert_eq!(MockClock::resolution(), Duration::from_millis(1));
+                assert_eq!(MockClock::system_time_resolution(), Duration::from_secs(1));
+
+                MockClock::advance(Duration::from_micros(1_500));
+                MockClock::advance_system_time(Duration::from_micros(1_500));
+                assert_eq!(Instant::now(), Instant(Duration::from_millis(2_345_601)));
+                assert_eq!(SystemTime::now(), SystemTime(Duration::from_secs(2_345)));
+
+                MockClock::set_resolution(Duration::ZERO);
+                assert_eq!(MockClock::system_time_resolution(), Duration::from_nanos(1));
+                reset_time();
+                reset_system_time();
+            }
+
+            #[test]
+            fn strictly_monotonic() {
+                let _lock = crate::test_lock();
+                reset_time();
+
+                MockClock::set_strictly_monotonic(true);
+                assert!(!MockClock::is_frozen());
+                let first = Instant::now();
+                let second = Instant::now();
+                let third = Instant::now();
+                assert_eq!(second - first, Duration::from_nanos(1));
+                assert_eq!(third - second, Duration::from_nanos(1));
+
+                MockClock::set_strictly_monotonic(false);
+                assert!(MockClock::is_frozen());
+                assert_eq!(Instant::now(), Instant::now());
+                reset_time();
+            }
+
+            #[test]
+            fn simulated_for_real() {
+                let _lock = crate::test_lock();
+                let real = Duration::from_secs(10);
+                assert_eq!(MockClock::simulated_for_real(real), Duration::ZERO);
+
+                for (factor, simulated) in [(1.0, 10), (2.0, 20), (0.5, 5)] {
+                    MockClock::set_realtime_scaled(factor);
+                    assert_eq!(MockClock::simulated_for_real(real), Duration::from_secs(simulated));
+                }
+
+                MockClock::stop_realtime();
+                reset_time();
+            }
+
+            #[test]
+            fn bounded() {
+                let max = Duration::from_secs(10);
+
+                assert_eq!(
+                    Instant::bounded(Duration::from_secs(3), max),
+                    Instant(Duration::from_secs(3))
+                );
+                assert_eq!(Instant::bounded(max, max), Instant(max));
+                assert_eq!(Instant::bounded(Duration::MAX, max), Instant(max));
+            }
         }
     };
 }
 
+pub(super) use define_boot_instant;
 pub(super) use define_instant;
 pub(super) use define_instant_tests;
 pub(super) use define_mock_clock;
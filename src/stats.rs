@@ -0,0 +1,44 @@
+use std::{collections::BTreeMap, time::Duration};
+
+/// Group `samples` into buckets `bucket` wide, and count the samples in each
+///
+/// This returns `(bucket start, count)` pairs, ordered by the start of the bucket. Empty buckets are left out.
+///
+/// # Panics
+/// If `bucket` is `Duration::ZERO`
+pub fn bucketize(samples: &[Duration], bucket: Duration) -> Vec<(Duration, usize)> {
+    assert!(!bucket.is_zero(), "bucket width must not be zero");
+
+    let mut buckets = BTreeMap::<Duration, usize>::new();
+    for &sample in samples {
+        *buckets.entry(crate::quantize(sample, bucket)).or_default() += 1;
+    }
+    buckets.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucketize() {
+        let samples = [5, 12, 18, 3, 45, 10, 0].map(Duration::from_millis);
+
+        assert_eq!(
+            super::bucketize(&samples, Duration::from_millis(10)),
+            [
+                (Duration::ZERO, 3),
+                (Duration::from_millis(10), 3),
+                (Duration::from_millis(40), 1),
+            ]
+        );
+
+        assert!(super::bucketize(&[], Duration::from_millis(10)).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "bucket width must not be zero")]
+    fn bucketize_zero_width() {
+        super::bucketize(&[Duration::from_millis(1)], Duration::ZERO);
+    }
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+pub use crate::global::{SystemTime, UNIX_EPOCH};
+
+/// A Mock clock, which only provides the [`SystemTime`] clock
+///
+/// This shares its state with [`global::MockClock`](crate::global::MockClock)
+#[derive(Copy, Clone, Debug)]
+pub struct MockClock;
+
+impl MockClock {
+    /// Set the internal [`SystemTime`] clock to this [`Duration`]
+    pub fn set_system_time(time: Duration) {
+        crate::global::MockClock::set_system_time(time)
+    }
+
+    /// Advance the internal [`SystemTime`] clock by this [`Duration`]
+    pub fn advance_system_time(time: Duration) {
+        crate::global::MockClock::advance_system_time(time)
+    }
+
+    /// Advance the internal [`SystemTime`] clock by this [`Duration`], and get a [`SystemTime`] at the new time
+    pub fn advance_system_then_now(time: Duration) -> SystemTime {
+        crate::global::MockClock::advance_system_then_now(time)
+    }
+
+    /// Get a [`SystemTime`] which is `after` from now, saturating rather than overflowing
+    pub fn system_deadline(after: Duration) -> SystemTime {
+        crate::global::MockClock::system_deadline(after)
+    }
+
+    /// Get the current [`SystemTime`] duration
+    pub fn system_time() -> Duration {
+        crate::global::MockClock::system_time()
+    }
+
+    /// Is this MockClock thread-local?
+    pub const fn is_thread_local() -> bool {
+        false
+    }
+}
+
+impl crate::HasClockMode for MockClock {
+    type Mode = crate::global::GlobalMode;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_global_system_time() {
+        let _lock = crate::test_lock();
+
+        MockClock::set_system_time(Duration::from_secs(3));
+        assert_eq!(
+            crate::global::MockClock::system_time(),
+            Duration::from_secs(3)
+        );
+
+        let now = SystemTime::now();
+        MockClock::advance_system_time(Duration::from_secs(2));
+        assert_eq!(now.elapsed().unwrap(), Duration::from_secs(2));
+        assert_eq!(MockClock::system_time(), Duration::from_secs(5));
+        assert!(!MockClock::is_thread_local());
+    }
+
+    #[test]
+    fn global_mode() {
+        fn requires_global<C: crate::HasClockMode<Mode = crate::global::GlobalMode>>() {}
+        requires_global::<MockClock>();
+    }
+}
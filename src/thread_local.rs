@@ -1,24 +1,311 @@
-use std::{cell::RefCell, time::Duration};
+use std::{
+    cell::{Cell, RefCell},
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+type SpawnHook = Arc<dyn Fn() -> (Duration, Duration) + Send + Sync>;
+type SharedClocks = Arc<Mutex<(Duration, Duration)>>;
+type AdvanceCallback = Box<dyn FnMut(Duration) -> ControlFlow<()> + Send>;
+
+static SPAWN_HOOK: Mutex<Option<SpawnHook>> = Mutex::new(None);
+static LIVE_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+// counts a thread as live from when it first uses its clocks until it exits
+struct LiveThread;
+
+impl LiveThread {
+    fn new() -> Self {
+        LIVE_THREADS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for LiveThread {
+    fn drop(&mut self) {
+        LIVE_THREADS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
 std::thread_local! {
-    static TIME: RefCell<Duration> = const { RefCell::new(Duration::ZERO) };
-    static SYSTEM_TIME: RefCell<Duration> = const { RefCell::new(Duration::ZERO) };
+    static LIVE: LiveThread = LiveThread::new();
+    static SEED: (Duration, Duration) = spawn_seed();
+    static TIME: Cell<Duration> = Cell::new(SEED.with(|(time, _)| *time));
+    static SYSTEM_TIME: Cell<Duration> = Cell::new(SEED.with(|(_, system_time)| *system_time));
+    static BOOT_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    static LAST_OP: Cell<Option<crate::ClockOp>> = const { Cell::new(None) };
+    static ADVANCE_CALLBACKS: RefCell<Vec<AdvanceCallback>> = const { RefCell::new(Vec::new()) };
+    // the (instant, system_time) resolutions
+    static RESOLUTION: Cell<(Duration, Duration)> = const { Cell::new((Duration::ZERO, Duration::ZERO)) };
+    static FRAME_RATE: Cell<f64> = const { Cell::new(60.0) };
+    static PROCESS_START: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    static REALTIME: Cell<Option<crate::Realtime>> = const { Cell::new(None) };
+    static NOW_CALLS: Cell<u64> = const { Cell::new(0) };
+    static SYSTEM_PAUSED: Cell<bool> = const { Cell::new(false) };
+    static STRICTLY_MONOTONIC: Cell<bool> = const { Cell::new(false) };
+    static LAST_TOKEN: Cell<Option<(u128, u64)>> = const { Cell::new(None) };
+    static FLOOR: Cell<(Duration, crate::FloorMode)> = const { Cell::new((Duration::ZERO, crate::FloorMode::Error)) };
+    // the system time that reads are stalled at, and how many reads are left
+    static SYSTEM_STALL: Cell<Option<(Duration, usize)>> = const { Cell::new(None) };
+    // the (time, system_time) that `now()` returns, inside of `MockClock::with_frozen_now`
+    static FROZEN_NOW: Cell<Option<(Duration, Duration)>> = const { Cell::new(None) };
+    // the (time, system_time) used instead of this thread's own, set by `MockClock::share_with`
+    static SHARED: RefCell<Option<SharedClocks>> = const { RefCell::new(None) };
+    #[cfg(all(feature = "strict", debug_assertions))]
+    static STRICT_TOUCHED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn spawn_seed() -> (Duration, Duration) {
+    LIVE.with(|_| ());
+    let hook = SPAWN_HOOK.lock().unwrap().clone();
+    hook.map_or((Duration::ZERO, Duration::ZERO), |hook| hook())
+}
+
+// `Duration` is `Copy`, so the clocks are kept in a `Cell` and updated through a copy
+fn update<T: Copy, R>(cell: &Cell<T>, d: impl FnOnce(&mut T) -> R) -> R {
+    let mut value = cell.get();
+    let out = d(&mut value);
+    cell.set(value);
+    out
+}
+
+fn get_shared() -> Option<SharedClocks> {
+    SHARED.with(|t| t.borrow().clone())
 }
 
-fn with_time(d: impl Fn(&mut Duration)) {
-    TIME.with(|t| d(&mut t.borrow_mut()));
+// the shared clocks are only locked in `with_clocks`, while running its closure. so the closures given to these
+// must not use the clocks themselves, an update to both clocks has to go through `with_clocks`
+fn with_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
+    // captures the process uptime baseline on the first use of the clock
+    crate::process_start();
+    if get_shared().is_some() {
+        return with_clocks(|time, _| d(time));
+    }
+
+    let realtime = restart_realtime();
+    TIME.with(|t| {
+        update(t, |t| {
+            *t += realtime;
+            d(t)
+        })
+    })
 }
 
 fn get_time() -> Duration {
-    TIME.with(|t| *t.borrow())
+    // captures the process uptime baseline on the first use of the clock
+    crate::process_start();
+    let realtime = REALTIME
+        .with(|t| t.get())
+        .map_or(Duration::ZERO, |rt| rt.elapsed());
+    let time =
+        get_shared().map_or_else(|| TIME.with(|t| t.get()), |shared| shared.lock().unwrap().0);
+    time + realtime
+}
+
+// moves any realtime progress into the stored time
+fn restart_realtime() -> Duration {
+    REALTIME.with(|t| {
+        let mut realtime = t.get();
+        let elapsed = realtime.as_mut().map_or(Duration::ZERO, |rt| rt.restart());
+        t.set(realtime);
+        elapsed
+    })
+}
+
+fn get_realtime() -> Option<crate::Realtime> {
+    REALTIME.with(|t| t.get())
 }
 
-fn with_system_time(d: impl Fn(&mut Duration)) {
-    SYSTEM_TIME.with(|t| d(&mut t.borrow_mut()));
+fn set_realtime(realtime: Option<crate::Realtime>) {
+    with_time(|_| REALTIME.with(|t| t.set(realtime)));
+}
+
+fn with_system_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
+    if get_shared().is_some() {
+        return with_clocks(|_, system_time| d(system_time));
+    }
+    SYSTEM_TIME.with(|t| update(t, d))
+}
+
+// updates (time, system_time) together, so they don't have to be nested
+fn with_clocks<R>(d: impl FnOnce(&mut Duration, &mut Duration) -> R) -> R {
+    let realtime = restart_realtime();
+    if let Some(shared) = get_shared() {
+        let (time, system_time) = &mut *shared.lock().unwrap();
+        *time += realtime;
+        return d(time, system_time);
+    }
+
+    TIME.with(|t| {
+        SYSTEM_TIME.with(|s| {
+            update(t, |t| {
+                *t += realtime;
+                update(s, |s| d(t, s))
+            })
+        })
+    })
 }
 
 fn get_system_time() -> Duration {
-    SYSTEM_TIME.with(|t| *t.borrow())
+    get_shared().map_or_else(
+        || SYSTEM_TIME.with(|t| t.get()),
+        |shared| shared.lock().unwrap().1,
+    )
+}
+
+fn with_boot_time<R>(d: impl FnOnce(&mut Duration) -> R) -> R {
+    BOOT_TIME.with(|t| update(t, d))
+}
+
+fn get_boot_time() -> Duration {
+    BOOT_TIME.with(|t| t.get())
+}
+
+fn with_resolution<R>(d: impl FnOnce(&mut (Duration, Duration)) -> R) -> R {
+    RESOLUTION.with(|t| update(t, d))
+}
+
+fn get_resolution() -> (Duration, Duration) {
+    RESOLUTION.with(|t| t.get())
+}
+
+fn set_frame_rate(fps: f64) {
+    FRAME_RATE.with(|t| t.set(fps));
+}
+
+fn get_frame_rate() -> f64 {
+    FRAME_RATE.with(|t| t.get())
+}
+
+fn set_process_start(start: Duration) {
+    PROCESS_START.with(|t| t.set(start));
+}
+
+fn get_process_start() -> Duration {
+    PROCESS_START.with(|t| t.get())
+}
+
+fn count_now_call() {
+    // taking a `now()` first means this thread is using its clocks on its own
+    #[cfg(all(feature = "strict", debug_assertions))]
+    STRICT_TOUCHED.with(|t| t.set(true));
+    NOW_CALLS.with(|t| t.set(t.get() + 1));
+}
+
+fn get_now_calls() -> u64 {
+    NOW_CALLS.with(|t| t.get())
+}
+
+fn reset_now_calls() {
+    NOW_CALLS.with(|t| t.set(0));
+}
+
+fn set_system_paused(paused: bool) {
+    SYSTEM_PAUSED.with(|t| t.set(paused));
+}
+
+fn is_system_paused() -> bool {
+    SYSTEM_PAUSED.with(|t| t.get())
+}
+
+fn set_strictly_monotonic(enabled: bool) {
+    STRICTLY_MONOTONIC.with(|t| t.set(enabled));
+}
+
+fn is_strictly_monotonic() -> bool {
+    STRICTLY_MONOTONIC.with(|t| t.get())
+}
+
+fn get_frozen_now() -> Option<(Duration, Duration)> {
+    FROZEN_NOW.with(|t| t.get())
+}
+
+fn set_frozen_now(frozen: Option<(Duration, Duration)>) {
+    FROZEN_NOW.with(|t| t.set(frozen));
+}
+
+fn with_last_token<R>(d: impl FnOnce(&mut Option<(u128, u64)>) -> R) -> R {
+    LAST_TOKEN.with(|t| {
+        let mut token = t.get();
+        let out = d(&mut token);
+        t.set(token);
+        out
+    })
+}
+
+fn with_floor<R>(d: impl FnOnce(&mut (Duration, crate::FloorMode)) -> R) -> R {
+    FLOOR.with(|t| {
+        let mut floor = t.get();
+        let out = d(&mut floor);
+        t.set(floor);
+        out
+    })
+}
+
+fn get_floor() -> (Duration, crate::FloorMode) {
+    FLOOR.with(|t| t.get())
+}
+
+fn with_system_stall<R>(d: impl FnOnce(&mut Option<(Duration, usize)>) -> R) -> R {
+    SYSTEM_STALL.with(|t| {
+        let mut stall = t.get();
+        let out = d(&mut stall);
+        t.set(stall);
+        out
+    })
+}
+
+fn set_last_op(op: crate::ClockOp) {
+    #[cfg(all(feature = "strict", debug_assertions))]
+    check_strict(matches!(op, crate::ClockOp::Set(..)));
+    LAST_OP.with(|t| t.set(Some(op)));
+}
+
+fn get_last_op() -> Option<crate::ClockOp> {
+    LAST_OP.with(|t| t.get())
+}
+
+// a spawned thread whose first use of its clocks is an advance, before setting them or taking a `now()`, has no
+// baseline of its own, so it probably expects the clocks of the thread which spawned it. named threads, like the main
+// thread or the test harness's threads, are taken to use their clocks on their own
+#[cfg(all(feature = "strict", debug_assertions))]
+#[track_caller]
+fn check_strict(is_set: bool) {
+    let first = !STRICT_TOUCHED.with(|t| t.replace(true));
+    if is_set || !first {
+        return;
+    }
+
+    let this = std::thread::current();
+    let sharing = SPAWN_HOOK.lock().unwrap().is_some() || get_shared().is_some();
+    if sharing || this.name().is_some() {
+        return;
+    }
+    panic!(
+        "the thread-local MockClock was advanced on {:?} before it was set. thread-local clocks start at zero on \
+        every thread, use `MockClock::set_spawn_hook`, `MockClock::share_with` or the global clock to share them",
+        this.id()
+    );
+}
+
+fn add_advance_callback(f: AdvanceCallback) {
+    ADVANCE_CALLBACKS.with(|callbacks| callbacks.borrow_mut().push(f));
+}
+
+fn run_advance_callbacks(d: Duration) {
+    // the callbacks are taken out while they run, so they can use the clock themselves
+    let mut callbacks = ADVANCE_CALLBACKS.take();
+    callbacks.retain_mut(|f| f(d).is_continue());
+
+    ADVANCE_CALLBACKS.with(|current| {
+        let mut current = current.borrow_mut();
+        callbacks.append(&mut current);
+        *current = callbacks;
+    });
 }
 
 crate::macros::define_mock_clock! {
@@ -26,18 +313,88 @@ crate::macros::define_mock_clock! {
     /// This uses thread-local state for the deterministic clock
 }
 
+impl MockClock {
+    /// Seed each thread's clocks from this hook, the first time that thread uses them
+    ///
+    /// The hook returns `(time, system_time)`. Threads which have already used their clocks are not affected.
+    ///
+    /// The hook must not use this `MockClock` itself.
+    pub fn set_spawn_hook(hook: impl Fn() -> (Duration, Duration) + Send + Sync + 'static) {
+        *SPAWN_HOOK.lock().unwrap() = Some(Arc::new(hook));
+    }
+
+    /// Remove the hook set by [`MockClock::set_spawn_hook`], new threads will start at `Duration::ZERO`
+    pub fn clear_spawn_hook() {
+        *SPAWN_HOOK.lock().unwrap() = None;
+    }
+
+    /// Use these shared `(time, system_time)` clocks on this thread, instead of its own
+    ///
+    /// Every thread given the same `clocks` sees the others' changes, until it calls [`MockClock::stop_sharing`].
+    /// The thread's own clocks are left as they were, and are used again after that.
+    pub fn share_with(clocks: Arc<Mutex<(Duration, Duration)>>) {
+        // moves any realtime progress into the clocks being left
+        with_time(|_| ());
+        SHARED.with(|t| *t.borrow_mut() = Some(clocks));
+    }
+
+    /// Go back to this thread's own clocks, after [`MockClock::share_with`]
+    pub fn stop_sharing() {
+        with_time(|_| ());
+        SHARED.with(|t| *t.borrow_mut() = None);
+    }
+
+    /// How many threads, which have used their clocks, are still running?
+    pub fn live_thread_count() -> usize {
+        LIVE_THREADS.load(Ordering::SeqCst)
+    }
+}
+
+/// The [`ClockMode`](crate::ClockMode) of this module's [`MockClock`], which has a new state per thread
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ThreadLocalMode;
+
+impl crate::ClockMode for ThreadLocalMode {
+    const IS_THREAD_LOCAL: bool = true;
+}
+
+impl crate::HasClockMode for MockClock {
+    type Mode = ThreadLocalMode;
+}
+
+impl MockClock {
+    /// The mode of this [`MockClock`], as a marker type
+    pub const MODE: ThreadLocalMode = ThreadLocalMode;
+}
+
 crate::macros::define_instant! {
     MockClock::time;
     true;
     /// This uses a thread-local cell for its time source
 }
 
+/// Reinterpret a [`global::Instant`](crate::global::Instant) as an [`Instant`] on this module's clock
+///
+/// This copies the instant's offset from its clock's start. The two clocks aren't connected, so the converted
+/// [`Instant`] is only meaningful if this clock was kept in step with the global clock.
+impl From<crate::global::Instant> for Instant {
+    fn from(value: crate::global::Instant) -> Self {
+        Self::from(value.into_inner())
+    }
+}
+
 crate::macros::define_system_time! {
     MockClock::system_time;
     true;
     /// This uses a thread-local cell for its time source
 }
 
+crate::macros::define_boot_instant! {
+    MockClock::boot_time;
+    true;
+    /// This uses a thread-local cell for its time source
+}
+
 crate::macros::define_instant_tests!();
 
 #[cfg(test)]
@@ -45,8 +402,27 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn mode_marker() {
+        use crate::{ClockMode, HasClockMode};
+
+        fn is_thread_local<C: HasClockMode>() -> bool {
+            C::Mode::IS_THREAD_LOCAL
+        }
+        fn requires_mode<C: HasClockMode<Mode = ThreadLocalMode>>() {}
+
+        requires_mode::<MockClock>();
+        assert_eq!(is_thread_local::<MockClock>(), MockClock::is_thread_local());
+        assert_eq!(MockClock::MODE, ThreadLocalMode);
+        assert_ne!(
+            <MockClock as HasClockMode>::Mode::IS_THREAD_LOCAL,
+            crate::global::GlobalMode::IS_THREAD_LOCAL
+        );
+    }
+
     #[test]
     fn is_thread_local() {
+        let _lock = crate::test_lock();
         assert!(MockClock::is_thread_local());
         assert!(Instant::now().is_thread_local());
         assert!(SystemTime::now().is_thread_local());
@@ -55,6 +431,7 @@ mod tests {
     // this checks that threads get their own time source
     #[test]
     fn thread_locality() {
+        let _lock = crate::test_lock();
         MockClock::set_time(Duration::ZERO);
 
         let start = Instant::now();
@@ -78,4 +455,264 @@ mod tests {
             handle.join().unwrap();
         }
     }
+
+    #[test]
+    fn spawn_hook() {
+        let _lock = crate::test_lock();
+
+        MockClock::set_spawn_hook(|| (Duration::from_secs(5), Duration::from_secs(50)));
+
+        let handles = [
+            std::thread::spawn(|| {
+                assert_eq!(MockClock::time(), Duration::from_secs(5));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(50));
+            }),
+            std::thread::spawn(|| {
+                MockClock::advance(Duration::from_secs(1));
+                assert_eq!(MockClock::time(), Duration::from_secs(6));
+                assert_eq!(MockClock::system_time(), Duration::from_secs(50));
+            }),
+        ];
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        MockClock::clear_spawn_hook();
+
+        std::thread::spawn(|| {
+            assert_eq!(MockClock::time(), Duration::ZERO);
+            assert_eq!(MockClock::system_time(), Duration::ZERO);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn live_thread_count() {
+        let _lock = crate::test_lock();
+
+        let started = std::sync::Arc::new(std::sync::Barrier::new(4));
+        let finish = std::sync::Arc::new(std::sync::Barrier::new(4));
+        let handles: Vec<_> = (0..3)
+            .map(|_| {
+                let (started, finish) = (started.clone(), finish.clone());
+                std::thread::spawn(move || {
+                    // set up first, so `strict` can't panic before the barrier
+                    MockClock::set_time(Duration::ZERO);
+                    MockClock::advance(Duration::from_secs(1));
+                    started.wait();
+                    finish.wait();
+                })
+            })
+            .collect();
+
+        // every test using thread-local clocks holds the lock, so other threads can only finish while this runs
+        started.wait();
+        let during = MockClock::live_thread_count();
+        assert!(during >= 3);
+        finish.wait();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(MockClock::live_thread_count() + 3 <= during);
+    }
+
+    #[test]
+    #[cfg(all(feature = "strict", debug_assertions))]
+    fn strict_advance_from_another_thread() {
+        let _lock = crate::test_lock();
+        MockClock::set_time(Duration::from_secs(10));
+
+        let err = std::thread::spawn(|| MockClock::advance(Duration::from_secs(1)))
+            .join()
+            .unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert!(msg.contains("advanced on"), "{msg}");
+
+        // threads which set up their own clocks don't get in each other's way
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = [1, 2]
+            .map(|secs| {
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    MockClock::set_time(Duration::from_secs(secs));
+                    barrier.wait();
+                    MockClock::advance(Duration::from_secs(secs));
+                    assert_eq!(MockClock::time(), Duration::from_secs(secs * 2));
+                })
+            })
+            .into_iter()
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // setting the clock or taking a `now()` first is fine
+        std::thread::spawn(|| {
+            MockClock::set_time(Duration::ZERO);
+            MockClock::advance(Duration::from_secs(1));
+        })
+        .join()
+        .unwrap();
+        std::thread::spawn(|| {
+            let start = Instant::now();
+            MockClock::advance(Duration::from_secs(1));
+            assert_eq!(start.elapsed(), Duration::from_secs(1));
+        })
+        .join()
+        .unwrap();
+
+        // and so is a named thread
+        std::thread::Builder::new()
+            .name("independent".into())
+            .spawn(|| MockClock::advance(Duration::from_secs(1)))
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn share_with() {
+        let _lock = crate::test_lock();
+        MockClock::set_time(Duration::from_secs(1));
+
+        let clocks = Arc::new(Mutex::new((Duration::ZERO, Duration::ZERO)));
+
+        let shared = clocks.clone();
+        std::thread::spawn(move || {
+            MockClock::share_with(shared);
+            MockClock::advance(Duration::from_secs(3));
+            MockClock::advance_system_time(Duration::from_secs(30));
+        })
+        .join()
+        .unwrap();
+
+        let shared = clocks.clone();
+        std::thread::spawn(move || {
+            MockClock::share_with(shared);
+            assert_eq!(MockClock::time(), Duration::from_secs(3));
+            assert_eq!(MockClock::system_time(), Duration::from_secs(30));
+            MockClock::advance(Duration::from_secs(2));
+
+            MockClock::stop_sharing();
+            assert_eq!(MockClock::time(), Duration::ZERO);
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(
+            *clocks.lock().unwrap(),
+            (Duration::from_secs(5), Duration::from_secs(30))
+        );
+        assert_eq!(MockClock::time(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn cell_storage() {
+        let _lock = crate::test_lock();
+        MockClock::set_time(Duration::ZERO);
+        MockClock::set_system_time(Duration::ZERO);
+
+        let start = Instant::now();
+        for i in 1..=1_000 {
+            MockClock::advance(Duration::from_millis(1));
+            MockClock::advance_system_time(Duration::from_millis(2));
+            assert_eq!(start.elapsed(), Duration::from_millis(i));
+        }
+        assert_eq!(
+            MockClock::advance_then_now(Duration::ZERO),
+            Instant(Duration::from_secs(1))
+        );
+        assert_eq!(MockClock::system_time(), Duration::from_secs(2));
+
+        // a nested update of one clock from inside another still sticks
+        MockClock::warp_to(Duration::from_secs(5), Duration::from_secs(6));
+        assert_eq!(MockClock::system_time(), Duration::from_secs(5));
+        assert_eq!(MockClock::time(), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn warp_to_shared() {
+        let _lock = crate::test_lock();
+        let clocks = Arc::new(Mutex::new((Duration::from_secs(1), Duration::from_secs(2))));
+
+        MockClock::share_with(clocks.clone());
+        let previous = MockClock::warp_to(Duration::from_secs(20), Duration::from_secs(10));
+        MockClock::stop_sharing();
+
+        assert_eq!(previous, (Duration::from_secs(2), Duration::from_secs(1)));
+        assert_eq!(
+            *clocks.lock().unwrap(),
+            (Duration::from_secs(10), Duration::from_secs(20))
+        );
+    }
+
+    #[test]
+    fn advance_everything_shared() {
+        let _lock = crate::test_lock();
+        MockClock::set_boot_time(Duration::ZERO);
+        let clocks = Arc::new(Mutex::new((Duration::from_secs(1), Duration::from_secs(2))));
+
+        MockClock::share_with(clocks.clone());
+        MockClock::advance_everything(Duration::from_secs(5));
+        MockClock::stop_sharing();
+
+        assert_eq!(
+            *clocks.lock().unwrap(),
+            (Duration::from_secs(6), Duration::from_secs(7))
+        );
+        assert_eq!(MockClock::boot_time(), Duration::from_secs(5));
+        MockClock::set_boot_time(Duration::ZERO);
+    }
+
+    #[test]
+    fn nested_operations_shared() {
+        let _lock = crate::test_lock();
+        let clocks = Arc::new(Mutex::new((Duration::ZERO, Duration::ZERO)));
+        MockClock::share_with(clocks.clone());
+
+        // callbacks run while an advance is in progress, and use the clocks themselves
+        MockClock::on_advance_cancellable(|d| {
+            MockClock::advance_system_time(d * 2);
+            let _ = MockClock::snapshot();
+            std::ops::ControlFlow::Break(())
+        });
+        MockClock::advance(Duration::from_secs(1));
+
+        MockClock::with_frozen_now(|| {
+            MockClock::warp_to(
+                MockClock::system_time() + Duration::from_secs(1),
+                MockClock::time(),
+            );
+            MockClock::advance_everything(Duration::from_secs(1));
+        });
+        MockClock::set_realtime_scaled(0.0);
+        MockClock::stop_realtime();
+        MockClock::stop_sharing();
+
+        assert_eq!(
+            *clocks.lock().unwrap(),
+            (Duration::from_secs(2), Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn realtime_around_share_with() {
+        let _lock = crate::test_lock();
+        MockClock::set_time(Duration::ZERO);
+        let clocks = Arc::new(Mutex::new((Duration::ZERO, Duration::ZERO)));
+
+        // progress made before switching stays with the clocks being left
+        MockClock::set_realtime_scaled(1000.0);
+        std::thread::sleep(Duration::from_millis(10));
+        MockClock::share_with(clocks.clone());
+        std::thread::sleep(Duration::from_millis(10));
+        MockClock::stop_sharing();
+        MockClock::stop_realtime();
+
+        assert!(MockClock::time() >= Duration::from_secs(10));
+        assert!(clocks.lock().unwrap().0 >= Duration::from_secs(10));
+    }
 }
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+/// Get the sign and magnitude of `a - b`
+///
+/// The `bool` is `true` when the difference is negative, that is when `a` is less than `b`
+pub fn signed_diff(a: Duration, b: Duration) -> (bool, Duration) {
+    (a < b, a.abs_diff(b))
+}
+
+/// The signed difference between two [`Duration`]s
+///
+/// This displays with a leading `+` or `-`, e.g. `+1.5s` or `-20ms`. Equal durations display as `0ns`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SignedDiff {
+    negative: bool,
+    magnitude: Duration,
+}
+
+impl SignedDiff {
+    /// Get the signed difference of `a - b`
+    pub fn new(a: Duration, b: Duration) -> Self {
+        let (negative, magnitude) = signed_diff(a, b);
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    /// Is `a` less than `b`?
+    pub const fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Get the absolute difference between `a` and `b`
+    pub const fn magnitude(&self) -> Duration {
+        self.magnitude
+    }
+}
+
+impl std::fmt::Display for SignedDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.negative, self.magnitude.is_zero()) {
+            (_, true) => write!(f, "{:?}", self.magnitude),
+            (true, false) => write!(f, "-{:?}", self.magnitude),
+            (false, false) => write!(f, "+{:?}", self.magnitude),
+        }
+    }
+}
+
+/// Assert that each item is greater than the one before it, like the instants in an ordered event log
+///
+/// This works with the `Instant`s (and `SystemTime`s) of either clock module.
+///
+/// # Panics
+/// If any item isn't greater than the one before it, reporting the pair's indices and values
+#[track_caller]
+pub fn assert_strictly_increasing<T: PartialOrd + std::fmt::Debug>(items: &[T]) {
+    for (i, pair) in items.windows(2).enumerate() {
+        assert!(
+            pair[1] > pair[0],
+            "item {} ({:?}) is not greater than item {i} ({:?})",
+            i + 1,
+            pair[1],
+            pair[0]
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_diff() {
+        let (a, b) = (Duration::from_millis(1500), Duration::from_millis(500));
+
+        assert_eq!(super::signed_diff(a, b), (false, Duration::from_secs(1)));
+        assert_eq!(super::signed_diff(b, a), (true, Duration::from_secs(1)));
+        assert_eq!(super::signed_diff(a, a), (false, Duration::ZERO));
+    }
+
+    #[test]
+    fn signed_diff_display() {
+        let (a, b) = (Duration::from_millis(1500), Duration::from_millis(1480));
+
+        assert_eq!(SignedDiff::new(a, b).to_string(), "+20ms");
+        assert_eq!(SignedDiff::new(b, a).to_string(), "-20ms");
+        assert_eq!(SignedDiff::new(a, a).to_string(), "0ns");
+
+        assert!(SignedDiff::new(b, a).is_negative());
+        assert_eq!(SignedDiff::new(b, a).magnitude(), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn strictly_increasing() {
+        use crate::global::Instant;
+
+        let instants = [1, 2, 5].map(|secs| Instant::from(Duration::from_secs(secs)));
+        assert_strictly_increasing(&instants);
+        assert_strictly_increasing::<Instant>(&[]);
+        assert_strictly_increasing(&instants[..1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "item 2 (Instant(2s)) is not greater than item 1 (Instant(2s))")]
+    fn not_strictly_increasing() {
+        use crate::global::Instant;
+
+        let instants = [1, 2, 2, 3].map(|secs| Instant::from(Duration::from_secs(secs)));
+        assert_strictly_increasing(&instants);
+    }
+}